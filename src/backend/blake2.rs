@@ -0,0 +1,46 @@
+use rust_blake2::{ Blake2b512, Blake2bVar, Blake2s256, Blake2sVar, Digest };
+use rust_blake2::digest::{ Update, VariableOutput };
+
+use backend::HashState;
+
+digest_states!(
+    new_blake2b_512 => Blake2b512,
+    new_blake2s_256 => Blake2s256);
+
+/// Returns `None` if the length is outside the range BLAKE2b supports.
+pub fn new_blake2b(length: usize) -> Option<Blake2bVar> {
+    if length >= 1 && length <= 64 {
+        Blake2bVar::new(length).ok()
+    } else {
+        None
+    }
+}
+
+/// Returns `None` if the length is outside the range BLAKE2s supports.
+pub fn new_blake2s(length: usize) -> Option<Blake2sVar> {
+    if length >= 1 && length <= 32 {
+        Blake2sVar::new(length).ok()
+    } else {
+        None
+    }
+}
+
+impl HashState for Blake2bVar {
+    fn update(&mut self, data: &[u8]) {
+        Update::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        VariableOutput::finalize_boxed(*self).into_vec()
+    }
+}
+
+impl HashState for Blake2sVar {
+    fn update(&mut self, data: &[u8]) {
+        Update::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        VariableOutput::finalize_boxed(*self).into_vec()
+    }
+}
@@ -0,0 +1,25 @@
+use blake3::Hasher;
+
+use backend::HashState;
+
+/// A BLAKE3 state along with the length of output to read from it.
+pub struct Blake3 {
+    hasher: Hasher,
+    length: usize,
+}
+
+pub fn new(length: usize) -> Blake3 {
+    Blake3 { hasher: Hasher::new(), length: length }
+}
+
+impl HashState for Blake3 {
+    fn update(&mut self, data: &[u8]) {
+        let _ = self.hasher.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let mut digest = vec![0; self.length];
+        self.hasher.finalize_xof().fill(&mut digest);
+        digest
+    }
+}
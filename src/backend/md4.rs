@@ -0,0 +1,5 @@
+use md4::{ Digest, Md4 };
+
+use backend::HashState;
+
+digest_states!(new => Md4);
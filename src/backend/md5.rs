@@ -0,0 +1,5 @@
+use md5::{ Digest, Md5 };
+
+use backend::HashState;
+
+digest_states!(new => Md5);
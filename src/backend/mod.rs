@@ -0,0 +1,221 @@
+//! Pluggable implementations of the hash algorithms used to generate
+//! multihashes.
+//!
+//! By default `MultiHasher` uses the `DefaultBackend`, which provides every
+//! algorithm enabled through cargo features along with any application
+//! specific implementations registered in the `registry`. To use a different
+//! implementation for some variants wrap the `DefaultBackend` in your own
+//! `HashBackend` and pass it to `MultiHasher::with_backend`.
+
+/// Implements `HashState` for each RustCrypto `Digest` type, along with a
+/// constructor function for it. Expects the `Digest` trait to be in scope.
+#[allow(unused_macros)] // Will be unused if no RustCrypto backends are active
+macro_rules! digest_states {
+    ($($new:ident => $digest:ident),+) => {
+        $(
+            pub fn $new() -> $digest {
+                $digest::new()
+            }
+
+            impl HashState for $digest {
+                fn update(&mut self, data: &[u8]) {
+                    Digest::update(self, data);
+                }
+
+                fn finalize(self: Box<Self>) -> Vec<u8> {
+                    Digest::finalize(*self).to_vec()
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "blake2")]
+mod blake2;
+#[cfg(feature = "blake3")]
+mod blake3;
+#[cfg(feature = "md4")]
+mod md4;
+#[cfg(feature = "md5")]
+mod md5;
+#[cfg(feature = "murmur3")]
+mod murmur3;
+#[cfg(feature = "ripemd")]
+mod ripemd;
+#[cfg(feature = "sha1")]
+mod sha1;
+#[cfg(feature = "sha2")]
+mod sha2;
+#[cfg(feature = "sha3")]
+mod sha3;
+#[cfg(feature = "xxhash")]
+mod xxhash;
+
+
+use registry;
+use MultiHashVariant;
+
+/// The in-progress state of a single hash computation.
+pub trait HashState: Send {
+    /// Feed the next chunk of data into the hash.
+    fn update(&mut self, data: &[u8]);
+
+    /// Consume the state and return the digest of all data fed in.
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+}
+
+/// A provider of hash implementations for multihash variants.
+///
+/// # Examples
+///
+/// Providing an implementation for a variant the crate does not support,
+/// while falling back to the default implementations for everything else:
+///
+/// ```rust
+/// # #[cfg(feature = "generation")] {
+/// use mhash::{ MultiHasher, MultiHashVariant };
+/// use mhash::backend::{ DefaultBackend, HashBackend, HashState };
+///
+/// struct Identity(Vec<u8>);
+///
+/// impl HashState for Identity {
+///     fn update(&mut self, data: &[u8]) {
+///         self.0.extend_from_slice(data);
+///     }
+///
+///     fn finalize(self: Box<Self>) -> Vec<u8> {
+///         self.0
+///     }
+/// }
+///
+/// struct IdentityBackend;
+///
+/// impl HashBackend for IdentityBackend {
+///     fn new_state(&self, variant: MultiHashVariant, length: usize) -> Option<Box<dyn HashState>> {
+///         match variant {
+///             MultiHashVariant::Identity => Some(Box::new(Identity(Vec::new()))),
+///             _ => DefaultBackend.new_state(variant, length),
+///         }
+///     }
+/// }
+///
+/// let mut hasher = MultiHasher::with_backend(
+///     MultiHashVariant::Identity, &IdentityBackend).unwrap();
+/// hasher.update(b"hello");
+/// assert_eq!(hasher.finalize().digest(), b"hello");
+/// # }
+/// ```
+pub trait HashBackend {
+    /// Returns a fresh state for the given variant producing a digest of the
+    /// given length, or `None` if this backend has no implementation of the
+    /// variant. The length has already been checked against the variant, it
+    /// only needs to be used by extendable-output variants.
+    fn new_state(&self, variant: MultiHashVariant, length: usize) -> Option<Box<dyn HashState>>;
+}
+
+/// The backend providing the hash implementations enabled through cargo
+/// features, and those registered for application specific variants.
+///
+/// SHA-256 and SHA-512 (including double SHA-256) use libsodium when the
+/// `sodiumoxide-sha2` feature is enabled, otherwise a pure Rust
+/// implementation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultBackend;
+
+impl HashBackend for DefaultBackend {
+    #[allow(unused_variables)] // length will be unused if no XOFs are active
+    fn new_state(&self, variant: MultiHashVariant, length: usize) -> Option<Box<dyn HashState>> {
+        match variant {
+            #[cfg(feature = "md4")]
+            MultiHashVariant::Md4 => Some(Box::new(md4::new())),
+            #[cfg(feature = "md5")]
+            MultiHashVariant::Md5 => Some(Box::new(md5::new())),
+            #[cfg(feature = "sha1")]
+            MultiHashVariant::Sha1 => Some(Box::new(sha1::new())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::Sha2_256 => Some(Box::new(sha2::new_256())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::Sha2_512 => Some(Box::new(sha2::new_512())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::Sha2_224 => Some(Box::new(sha2::new_224())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::Sha2_384 => Some(Box::new(sha2::new_384())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::Sha2_512_224 => Some(Box::new(sha2::new_512_224())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::Sha2_512_256 => Some(Box::new(sha2::new_512_256())),
+            #[cfg(feature = "sha2")]
+            MultiHashVariant::DblSha2_256 => Some(Box::new(sha2::new_dbl_256())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Sha3_224 => Some(Box::new(sha3::new_224())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Sha3_256 => Some(Box::new(sha3::new_256())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Sha3_384 => Some(Box::new(sha3::new_384())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Sha3_512 => Some(Box::new(sha3::new_512())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Keccak224 => Some(Box::new(sha3::new_keccak224())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Keccak256 => Some(Box::new(sha3::new_keccak256())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Keccak384 => Some(Box::new(sha3::new_keccak384())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Keccak512 => Some(Box::new(sha3::new_keccak512())),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Shake128 => Some(Box::new(sha3::new_shake128(length))),
+            #[cfg(feature = "sha3")]
+            MultiHashVariant::Shake256 => Some(Box::new(sha3::new_shake256(length))),
+            #[cfg(feature = "blake2")]
+            MultiHashVariant::Blake2B => Some(Box::new(blake2::new_blake2b_512())),
+            #[cfg(feature = "blake2")]
+            MultiHashVariant::Blake2BSized { length } => blake2::new_blake2b(length).map(boxed),
+            #[cfg(feature = "blake2")]
+            MultiHashVariant::Blake2S => Some(Box::new(blake2::new_blake2s_256())),
+            #[cfg(feature = "blake2")]
+            MultiHashVariant::Blake2SSized { length } => blake2::new_blake2s(length).map(boxed),
+            #[cfg(feature = "ripemd")]
+            MultiHashVariant::Ripemd160 => Some(Box::new(ripemd::new_160())),
+            #[cfg(feature = "blake3")]
+            MultiHashVariant::Blake3 => Some(Box::new(blake3::new(length))),
+            #[cfg(feature = "murmur3")]
+            MultiHashVariant::Murmur3_32 => Some(Box::new(murmur3::Murmur3_32::new())),
+            #[cfg(feature = "murmur3")]
+            MultiHashVariant::Murmur3X64_64 => Some(Box::new(murmur3::Murmur3X64_64::new())),
+            #[cfg(feature = "xxhash")]
+            MultiHashVariant::Xxh32 => Some(Box::new(xxhash::new_32())),
+            #[cfg(feature = "xxhash")]
+            MultiHashVariant::Xxh64 => Some(Box::new(xxhash::new_64())),
+            #[cfg(feature = "xxhash")]
+            MultiHashVariant::Xxh3_64 => Some(Box::new(xxhash::Xxh3_64::new())),
+            #[cfg(feature = "xxhash")]
+            MultiHashVariant::Xxh3_128 => Some(Box::new(xxhash::Xxh3_128::new())),
+            MultiHashVariant::ApplicationSpecific { code } => registry::new_state(code),
+            _ => None,
+        }
+    }
+}
+
+#[allow(dead_code)] // Will be dead if blake2 is not active
+fn boxed<S: HashState + 'static>(state: S) -> Box<dyn HashState> {
+    Box::new(state)
+}
+
+/// Adapts a [RustCrypto][] `Digest` implementation into a `HashState`, for use
+/// in a custom `HashBackend`.
+///
+/// [RustCrypto]: https://github.com/RustCrypto/hashes
+#[cfg(feature = "digest")]
+#[derive(Debug, Clone, Default)]
+pub struct DigestState<D>(pub D);
+
+#[cfg(feature = "digest")]
+impl<D: digest::Digest + Send> HashState for DigestState<D> {
+    fn update(&mut self, data: &[u8]) {
+        digest::Digest::update(&mut self.0, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        digest::Digest::finalize(self.0).to_vec()
+    }
+}
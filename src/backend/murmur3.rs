@@ -0,0 +1,193 @@
+use std::cmp;
+
+use backend::HashState;
+
+// Incremental implementations of MurmurHash3 with a seed of 0, digests are
+// written big-endian.
+
+/// Collects data into fixed size blocks, keeping any partial block until more
+/// data arrives.
+struct Blocks {
+    tail: [u8; 16],
+    tail_len: usize,
+    len: u64,
+}
+
+impl Blocks {
+    fn new() -> Blocks {
+        Blocks { tail: [0; 16], tail_len: 0, len: 0 }
+    }
+
+    fn update<F: FnMut(&[u8])>(&mut self, size: usize, mut data: &[u8], mut process: F) {
+        self.len += data.len() as u64;
+        if self.tail_len > 0 {
+            let needed = size - self.tail_len;
+            if data.len() < needed {
+                self.tail[self.tail_len..self.tail_len + data.len()].copy_from_slice(data);
+                self.tail_len += data.len();
+                return;
+            }
+            self.tail[self.tail_len..size].copy_from_slice(&data[..needed]);
+            process(&self.tail[..size]);
+            self.tail_len = 0;
+            data = &data[needed..];
+        }
+        for block in data.chunks(size) {
+            if block.len() == size {
+                process(block);
+            } else {
+                self.tail[..block.len()].copy_from_slice(block);
+                self.tail_len = block.len();
+            }
+        }
+    }
+
+    fn tail(&self) -> &[u8] {
+        &self.tail[..self.tail_len]
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    bytes.iter().rev().fold(0, |value, &byte| (value << 8) | u32::from(byte))
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |value, &byte| (value << 8) | u64::from(byte))
+}
+
+const C1_32: u32 = 0xcc9e2d51;
+const C2_32: u32 = 0x1b873593;
+
+fn mix_k1_32(k1: u32) -> u32 {
+    k1.wrapping_mul(C1_32).rotate_left(15).wrapping_mul(C2_32)
+}
+
+pub struct Murmur3_32 {
+    blocks: Blocks,
+    h1: u32,
+}
+
+impl Murmur3_32 {
+    pub fn new() -> Murmur3_32 {
+        Murmur3_32 { blocks: Blocks::new(), h1: 0 }
+    }
+}
+
+impl HashState for Murmur3_32 {
+    fn update(&mut self, data: &[u8]) {
+        let h1 = &mut self.h1;
+        self.blocks.update(4, data, |block| {
+            *h1 ^= mix_k1_32(read_u32(block));
+            *h1 = h1.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+        });
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let mut h1 = self.h1;
+        let tail = self.blocks.tail();
+        if !tail.is_empty() {
+            h1 ^= mix_k1_32(read_u32(tail));
+        }
+        h1 ^= self.blocks.len as u32;
+        h1 ^= h1 >> 16;
+        h1 = h1.wrapping_mul(0x85ebca6b);
+        h1 ^= h1 >> 13;
+        h1 = h1.wrapping_mul(0xc2b2ae35);
+        h1 ^= h1 >> 16;
+        h1.to_be_bytes().to_vec()
+    }
+}
+
+const C1_64: u64 = 0x87c37b91114253d5;
+const C2_64: u64 = 0x4cf5ad432745937f;
+
+fn mix_k1_64(k1: u64) -> u64 {
+    k1.wrapping_mul(C1_64).rotate_left(31).wrapping_mul(C2_64)
+}
+
+fn mix_k2_64(k2: u64) -> u64 {
+    k2.wrapping_mul(C2_64).rotate_left(33).wrapping_mul(C1_64)
+}
+
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51afd7ed558ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ceb9fe1a85ec53);
+    k ^= k >> 33;
+    k
+}
+
+/// The first 64 bits of MurmurHash3 x64 128.
+pub struct Murmur3X64_64 {
+    blocks: Blocks,
+    h1: u64,
+    h2: u64,
+}
+
+impl Murmur3X64_64 {
+    pub fn new() -> Murmur3X64_64 {
+        Murmur3X64_64 { blocks: Blocks::new(), h1: 0, h2: 0 }
+    }
+}
+
+impl HashState for Murmur3X64_64 {
+    fn update(&mut self, data: &[u8]) {
+        let (h1, h2) = (&mut self.h1, &mut self.h2);
+        self.blocks.update(16, data, |block| {
+            *h1 ^= mix_k1_64(read_u64(&block[..8]));
+            *h1 = h1.rotate_left(27).wrapping_add(*h2).wrapping_mul(5).wrapping_add(0x52dce729);
+            *h2 ^= mix_k2_64(read_u64(&block[8..]));
+            *h2 = h2.rotate_left(31).wrapping_add(*h1).wrapping_mul(5).wrapping_add(0x38495ab5);
+        });
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let (mut h1, mut h2) = (self.h1, self.h2);
+        let tail = self.blocks.tail();
+        if tail.len() > 8 {
+            h2 ^= mix_k2_64(read_u64(&tail[8..]));
+        }
+        if !tail.is_empty() {
+            h1 ^= mix_k1_64(read_u64(&tail[..cmp::min(tail.len(), 8)]));
+        }
+        h1 ^= self.blocks.len;
+        h2 ^= self.blocks.len;
+        h1 = h1.wrapping_add(h2);
+        h2 = h2.wrapping_add(h1);
+        h1 = fmix64(h1);
+        h2 = fmix64(h2);
+        h1 = h1.wrapping_add(h2);
+        h1.to_be_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use backend::HashState;
+    use super::{ Murmur3_32, Murmur3X64_64 };
+
+    fn chunked<S: HashState + 'static>(new: fn() -> S, data: &[u8]) {
+        let mut whole = Box::new(new());
+        whole.update(data);
+        let expected = whole.finalize();
+        for size in 1..data.len() {
+            let mut state = Box::new(new());
+            for chunk in data.chunks(size) {
+                state.update(chunk);
+                state.update(&[]);
+            }
+            assert_eq!(state.finalize(), expected, "chunks of {}", size);
+        }
+    }
+
+    #[test]
+    fn chunked_32() {
+        chunked(Murmur3_32::new, b"The quick brown fox jumps over the lazy dog");
+    }
+
+    #[test]
+    fn chunked_x64_64() {
+        chunked(Murmur3X64_64::new, b"The quick brown fox jumps over the lazy dog");
+    }
+}
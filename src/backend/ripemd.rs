@@ -0,0 +1,5 @@
+use ripemd::{ Digest, Ripemd160 };
+
+use backend::HashState;
+
+digest_states!(new_160 => Ripemd160);
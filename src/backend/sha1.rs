@@ -0,0 +1,5 @@
+use sha1::{ Digest, Sha1 };
+
+use backend::HashState;
+
+digest_states!(new => Sha1);
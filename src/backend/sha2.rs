@@ -0,0 +1,79 @@
+use rust_sha2::{ Digest, Sha224, Sha384, Sha512_224, Sha512_256 };
+#[cfg(not(feature = "sodiumoxide-sha2"))]
+use rust_sha2::{ Sha256, Sha512 };
+#[cfg(feature = "sodiumoxide-sha2")]
+use sodiumoxide::crypto::hash::{ sha256, sha512 };
+
+use backend::HashState;
+
+#[cfg(feature = "sodiumoxide-sha2")]
+pub struct Sha256(sha256::State);
+
+#[cfg(feature = "sodiumoxide-sha2")]
+pub fn new_256() -> Sha256 {
+    Sha256(sha256::State::new())
+}
+
+#[cfg(feature = "sodiumoxide-sha2")]
+impl HashState for Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.finalize().0.to_vec()
+    }
+}
+
+#[cfg(feature = "sodiumoxide-sha2")]
+pub struct Sha512(sha512::State);
+
+#[cfg(feature = "sodiumoxide-sha2")]
+pub fn new_512() -> Sha512 {
+    Sha512(sha512::State::new())
+}
+
+#[cfg(feature = "sodiumoxide-sha2")]
+impl HashState for Sha512 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.finalize().0.to_vec()
+    }
+}
+
+#[cfg(not(feature = "sodiumoxide-sha2"))]
+digest_states!(
+    new_256 => Sha256,
+    new_512 => Sha512);
+
+/// SHA-256 applied to the SHA-256 digest of the data.
+pub struct DblSha256(Box<Sha256>);
+
+pub fn new_dbl_256() -> DblSha256 {
+    DblSha256(Box::new(new_256()))
+}
+
+impl HashState for DblSha256 {
+    fn update(&mut self, data: &[u8]) {
+        HashState::update(&mut *self.0, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let mut outer = Box::new(new_256());
+        HashState::update(&mut *outer, &HashState::finalize(self.0));
+        HashState::finalize(outer)
+    }
+}
+
+// libsodium only provides SHA-256 and SHA-512, the other members of the family
+// use different initial values so always come from the pure Rust
+// implementation.
+
+digest_states!(
+    new_224 => Sha224,
+    new_384 => Sha384,
+    new_512_224 => Sha512_224,
+    new_512_256 => Sha512_256);
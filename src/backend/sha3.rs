@@ -0,0 +1,42 @@
+use sha3::{ Digest, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256 };
+use sha3::{ Keccak224, Keccak256, Keccak384, Keccak512 };
+use sha3::digest::{ ExtendableOutput, Update, XofReader };
+
+use backend::HashState;
+
+digest_states!(
+    new_224 => Sha3_224,
+    new_256 => Sha3_256,
+    new_384 => Sha3_384,
+    new_512 => Sha3_512,
+    new_keccak224 => Keccak224,
+    new_keccak256 => Keccak256,
+    new_keccak384 => Keccak384,
+    new_keccak512 => Keccak512);
+
+/// A SHAKE state along with the length of output to read from it.
+pub struct Shake<S> {
+    state: S,
+    length: usize,
+}
+
+pub fn new_shake128(length: usize) -> Shake<Shake128> {
+    Shake { state: Shake128::default(), length: length }
+}
+
+pub fn new_shake256(length: usize) -> Shake<Shake256> {
+    Shake { state: Shake256::default(), length: length }
+}
+
+impl<S: ExtendableOutput + Send> HashState for Shake<S> {
+    fn update(&mut self, data: &[u8]) {
+        Update::update(&mut self.state, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let Shake { state, length } = *self;
+        let mut digest = vec![0; length];
+        state.finalize_xof().read(&mut digest);
+        digest
+    }
+}
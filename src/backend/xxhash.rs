@@ -0,0 +1,71 @@
+use xxhash::xxh3::Xxh3;
+use xxhash::xxh32::Xxh32;
+use xxhash::xxh64::Xxh64;
+
+use backend::HashState;
+
+// Digests are written big-endian, matching the canonical xxHash representation.
+
+pub fn new_32() -> Xxh32 {
+    Xxh32::new(0)
+}
+
+impl HashState for Xxh32 {
+    fn update(&mut self, data: &[u8]) {
+        Xxh32::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.digest().to_be_bytes().to_vec()
+    }
+}
+
+pub fn new_64() -> Xxh64 {
+    Xxh64::new(0)
+}
+
+impl HashState for Xxh64 {
+    fn update(&mut self, data: &[u8]) {
+        Xxh64::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.digest().to_be_bytes().to_vec()
+    }
+}
+
+pub struct Xxh3_64(Xxh3);
+
+impl Xxh3_64 {
+    pub fn new() -> Xxh3_64 {
+        Xxh3_64(Xxh3::new())
+    }
+}
+
+impl HashState for Xxh3_64 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.digest().to_be_bytes().to_vec()
+    }
+}
+
+pub struct Xxh3_128(Xxh3);
+
+impl Xxh3_128 {
+    pub fn new() -> Xxh3_128 {
+        Xxh3_128(Xxh3::new())
+    }
+}
+
+impl HashState for Xxh3_128 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.digest128().to_be_bytes().to_vec()
+    }
+}
@@ -17,6 +17,16 @@ pub mod creation {
                 description("unknown multihash code")
                 display("unknown multihash code: {}", code)
             }
+            InvalidVariant(variant: MultiHashVariant) {
+                description("invalid multihash variant")
+                display("invalid multihash variant {:?}", variant)
+            }
+            NotApplicationSpecific(code: usize) {
+                description("not an application specific multihash code")
+                display(
+                    "multihash code {:#04x} outside application specific range 0x0400-0x040f",
+                    code)
+            }
         }
     }
 }
@@ -62,3 +72,105 @@ pub mod parse {
     }
 }
 
+#[cfg(feature = "generation")]
+pub mod generation {
+    use std::io;
+    use MultiHashVariant;
+    use super::creation;
+
+    error_chain! {
+        links {
+            creation::Error, creation::ErrorKind, Creation;
+        }
+
+        foreign_links {
+            io::Error, Io;
+        }
+
+        errors {
+            UnsupportedVariant(variant: MultiHashVariant) {
+                description("no generator available for multihash variant")
+                display(
+                    "no generator available for hash kind {}",
+                    variant.name())
+            }
+            InvalidLength(variant: MultiHashVariant, length: usize) {
+                description("invalid digest length requested")
+                display(
+                    "cannot generate a {} byte digest for hash kind {}",
+                    length, variant.name())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "validation")]
+pub mod validation {
+    use std::error;
+    use std::fmt;
+    use std::io;
+
+    use { MultiHash, MultiHashVariant };
+
+    /// The details of a multihash that did not match the data it was
+    /// validated against.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Mismatch {
+        /// The multihash that was being validated.
+        pub expected: MultiHash,
+        /// The multihash the data actually hashed to, truncated to the same
+        /// length as the expected multihash.
+        pub computed: MultiHash,
+    }
+
+    impl fmt::Display for Mismatch {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{} digest mismatch: expected ", self.expected.name())?;
+            for byte in self.expected.digest() {
+                write!(f, "{:02x}", byte)?;
+            }
+            f.write_str(", computed ")?;
+            for byte in self.computed.digest() {
+                write!(f, "{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl error::Error for Mismatch {
+        fn description(&self) -> &str {
+            "multihash digest does not match data"
+        }
+    }
+
+    error_chain! {
+        foreign_links {
+            io::Error, Io;
+        }
+
+        errors {
+            NoValidator(variant: MultiHashVariant) {
+                description("no validator available for multihash variant")
+                display(
+                    "no validator available for hash kind {}",
+                    variant.name())
+            }
+            EmptyDigest(variant: MultiHashVariant) {
+                description("multihash digest is empty")
+                display(
+                    "multihash digest of hash kind {} is empty and would match any data",
+                    variant.name())
+            }
+            DigestTooLong(variant: MultiHashVariant, length: usize, hash_length: usize) {
+                description("multihash digest longer than hash output")
+                display(
+                    "multihash digest length {} longer than the {} byte output of hash kind {}",
+                    length, hash_length, variant.name())
+            }
+            Mismatch(mismatch: Box<Mismatch>) {
+                description("multihash digest does not match data")
+                display("{}", mismatch)
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fs::File;
+use std::io::{ self, Read };
+use std::path::Path;
+
+#[cfg(feature = "memmap2")]
+use memmap2::Mmap;
+
+/// Regular files at least this large are memory-mapped instead of read.
+#[cfg(feature = "memmap2")]
+const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// Feed the contents of the file at the given path to `update`.
+///
+/// With the `memmap2` feature large regular files are memory-mapped and fed
+/// in one go, everything else (small files, pipes, devices, and files on
+/// filesystems that cannot be mapped) is read in chunks.
+pub fn read_file(path: &Path, update: &mut dyn FnMut(&[u8])) -> io::Result<()> {
+    let mut file = File::open(path)?;
+
+    #[cfg(feature = "memmap2")]
+    {
+        let metadata = file.metadata()?;
+        if metadata.is_file() && metadata.len() >= MMAP_THRESHOLD {
+            // Unsafe as the file may be modified while mapped, that can only
+            // change what we hash (or fault if the file is truncated). Some
+            // network and FUSE filesystems do not support mapping, fall back
+            // to reading those.
+            if let Ok(map) = unsafe { Mmap::map(&file) } {
+                update(&map);
+                return Ok(());
+            }
+        }
+    }
+
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => update(&buffer[..read]),
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+
+    use super::read_file;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("mhash-{}-{}", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn small() {
+        let path = temp_file("small", b"hello world");
+        let mut read = Vec::new();
+        read_file(&path, &mut |data| read.extend_from_slice(data)).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read, b"hello world");
+    }
+
+    #[test]
+    fn large() {
+        let contents = (0..3 * 1024 * 1024).map(|i| i as u8).collect::<Vec<u8>>();
+        let path = temp_file("large", &contents);
+        let mut read = Vec::new();
+        read_file(&path, &mut |data| read.extend_from_slice(data)).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(read == contents);
+    }
+
+    #[test]
+    fn missing() {
+        let path = env::temp_dir().join(format!("mhash-{}-missing", process::id()));
+        assert!(read_file(&path, &mut |_| ()).is_err());
+    }
+
+    #[cfg(all(feature = "generation", feature = "validation", feature = "sha2"))]
+    #[test]
+    fn generate_and_validate() {
+        use { MultiHash, MultiHashVariant };
+        let path = temp_file("generate", b"hello world");
+        let multihash = MultiHash::generate_file(&path, MultiHashVariant::Sha2_256).unwrap();
+        assert_eq!(multihash, MultiHash::generate_sha2_256(b"hello world"));
+        assert!(multihash.verify_file(&path).is_ok());
+        assert_eq!(
+            MultiHash::generate_sha2_256(b"goodbye world").validate_file(&path).map(Result::ok),
+            Some(Some(false)));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,14 +1,261 @@
-#[cfg(feature = "sha2")]
-mod sha2;
+use std::fmt;
+use std::io;
+use std::path::Path;
 
-use MultiHash;
+use backend::{ DefaultBackend, HashBackend, HashState };
+use error;
+use file;
+use { MultiHash, MultiHashVariant };
 
+mod reader;
+mod set;
+mod writer;
+
+pub use self::reader::HashingReader;
+pub use self::set::MultiHasherSet;
+pub use self::writer::HashingWriter;
+
+/// The digest length generated for the variant when no length is requested.
+fn default_length(variant: MultiHashVariant) -> usize {
+    match variant {
+        MultiHashVariant::Shake128 | MultiHashVariant::Blake3 => 32,
+        MultiHashVariant::Shake256 => 64,
+        _ => variant.max_len(),
+    }
+}
+
+/// Incrementally generates a `MultiHash` from data supplied in chunks.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "sha2")] {
+/// use mhash::{ MultiHash, MultiHasher, MultiHashVariant };
+/// let mut hasher = MultiHasher::new(MultiHashVariant::Sha2_256).unwrap();
+/// hasher.update(b"hello ");
+/// hasher.update(b"world");
+/// assert_eq!(
+///     hasher.finalize(),
+///     MultiHash::generate_sha2_256(b"hello world"));
+/// # }
+/// ```
+pub struct MultiHasher {
+    variant: MultiHashVariant,
+    length: usize,
+    state: Box<dyn HashState>,
+}
+
+#[allow(len_without_is_empty)]
+impl MultiHasher {
+    /// Create a new hasher for the specified variant.
+    ///
+    /// Extendable-output variants produce a default length digest, 32 bytes
+    /// for `Shake128` and `Blake3` and 64 bytes for `Shake256`, use
+    /// `with_length` to pick a different length.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator for the variant
+    /// available, this depends on which features the crate was compiled with.
+    pub fn new(variant: MultiHashVariant) -> error::generation::Result<MultiHasher> {
+        MultiHasher::with_length(variant, default_length(variant))
+    }
+
+    /// Create a new hasher for the specified variant that will produce a
+    /// digest of the given length.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator for the variant
+    /// available, this depends on which features the crate was compiled with.
+    ///
+    /// Returns `InvalidLength` if the length is zero, or the variant is not
+    /// an extendable-output function and the length is longer than its
+    /// digest length.
+    ///
+    /// # Examples
+    ///
+    /// Non-extendable variants produce a truncated digest when given a
+    /// length shorter than their digest length:
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "sha2")] {
+    /// use mhash::{ MultiHash, MultiHasher, MultiHashVariant };
+    /// let mut hasher = MultiHasher::with_length(MultiHashVariant::Sha2_256, 20).unwrap();
+    /// hasher.update(b"hello world");
+    /// let multihash = hasher.finalize();
+    /// assert_eq!(multihash.len(), 20);
+    /// assert_eq!(
+    ///     multihash.digest(),
+    ///     &MultiHash::generate_sha2_256(b"hello world").digest()[..20]);
+    /// # }
+    /// ```
+    pub fn with_length(variant: MultiHashVariant, length: usize) -> error::generation::Result<MultiHasher> {
+        MultiHasher::with_length_and_backend(variant, length, &DefaultBackend)
+    }
+
+    /// Create a new hasher for the specified variant using the hash
+    /// implementation provided by the given backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if the backend has no implementation of
+    /// the variant.
+    pub fn with_backend(variant: MultiHashVariant, backend: &dyn HashBackend) -> error::generation::Result<MultiHasher> {
+        MultiHasher::with_length_and_backend(variant, default_length(variant), backend)
+    }
+
+    /// Create a new hasher for the specified variant that will produce a
+    /// digest of the given length, using the hash implementation provided by
+    /// the given backend.
+    ///
+    /// # Errors
+    ///
+    /// As for `with_length`, except the supported variants are determined by
+    /// the backend.
+    ///
+    /// Returns `Creation(InvalidVariant)` if the variant has fields outside
+    /// their allowed ranges, see `MultiHashVariant::check_valid`.
+    pub fn with_length_and_backend(variant: MultiHashVariant, length: usize, backend: &dyn HashBackend) -> error::generation::Result<MultiHasher> {
+        variant.check_valid()?;
+        // Application specific variants may be re-registered with a different
+        // maximum length at any time, so only look it up once
+        let max_len = variant.max_len();
+        if length == 0 || (!variant.is_extendable() && length > max_len) {
+            return Err(error::generation::ErrorKind::InvalidLength(variant, length).into());
+        }
+        // Fixed length variants always generate their full digest, which is
+        // truncated in `finalize`
+        let state_length = if variant.is_extendable() { length } else { max_len };
+        match backend.new_state(variant, state_length) {
+            Some(state) => Ok(MultiHasher { variant: variant, length: length, state: state }),
+            None => Err(error::generation::ErrorKind::UnsupportedVariant(variant).into()),
+        }
+    }
+
+    /// The variant of multihash this hasher will generate.
+    pub fn variant(&self) -> MultiHashVariant {
+        self.variant
+    }
+
+    /// The length of the digest this hasher will generate.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Feed the next chunk of data into the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        self.state.update(data);
+    }
+
+    /// Consume the hasher and return the `MultiHash` of all data fed into it.
+    ///
+    /// The digest is never longer than `len()`, but may be shorter if the
+    /// hash state returns fewer bytes, e.g. a registered application specific
+    /// implementation producing less than its registered maximum length or a
+    /// custom `HashBackend` ignoring the requested length.
+    pub fn finalize(self) -> MultiHash {
+        let mut digest = self.state.finalize();
+        digest.truncate(self.length);
+        MultiHash::new_unchecked(self.variant, &digest)
+    }
+}
+
+impl io::Write for MultiHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for MultiHasher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MultiHasher({})", self.variant.name())
+    }
+}
+
+impl MultiHash {
+    /// Generate a `MultiHash` of the specified variant for the given data.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator for the variant
+    /// available, this depends on which features the crate was compiled with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "sha2")] {
+    /// use mhash::{ MultiHash, MultiHashVariant };
+    /// assert_eq!(
+    ///     MultiHash::generate(MultiHashVariant::Sha2_256, b"hello world")
+    ///         .unwrap(),
+    ///     MultiHash::generate_sha2_256(b"hello world"));
+    /// # }
+    /// ```
+    pub fn generate(variant: MultiHashVariant, data: &[u8]) -> error::generation::Result<MultiHash> {
+        let mut hasher = MultiHasher::new(variant)?;
+        hasher.update(data);
+        Ok(hasher.finalize())
+    }
+
+    /// Generate a `MultiHash` of the specified variant with a digest of the
+    /// given length for the given data.
+    ///
+    /// Extendable-output variants (`Shake128`, `Shake256` and `Blake3`)
+    /// generate a digest of exactly this length, other variants generate
+    /// their digest truncated to this length, see `MultiHasher::with_length`
+    /// for the restrictions on the length.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "sha3")] {
+    /// use mhash::{ MultiHash, MultiHashVariant };
+    /// let multihash = MultiHash::generate_with_length(
+    ///     MultiHashVariant::Shake256, b"hello world", 32).unwrap();
+    /// assert_eq!(multihash.len(), 32);
+    /// # }
+    /// ```
+    pub fn generate_with_length(variant: MultiHashVariant, data: &[u8], length: usize) -> error::generation::Result<MultiHash> {
+        let mut hasher = MultiHasher::with_length(variant, length)?;
+        hasher.update(data);
+        Ok(hasher.finalize())
+    }
+
+    /// Generate a `MultiHash` of the specified variant for the contents of
+    /// the file at the given path.
+    ///
+    /// With the `memmap2` feature large regular files are memory-mapped,
+    /// other files are read in chunks. If a memory-mapped file is truncated
+    /// while being hashed the process may be killed by `SIGBUS`.
+    ///
+    /// # Errors
+    ///
+    /// As for `generate`, and additionally returns `Io` if the file cannot be
+    /// read.
+    pub fn generate_file<P: AsRef<Path>>(path: P, variant: MultiHashVariant) -> error::generation::Result<MultiHash> {
+        MultiHash::generate_file_with_backend(path, variant, &DefaultBackend)
+    }
+
+    /// As for `generate_file`, except the hash implementation is provided by
+    /// the given backend.
+    pub fn generate_file_with_backend<P: AsRef<Path>>(path: P, variant: MultiHashVariant, backend: &dyn HashBackend) -> error::generation::Result<MultiHash> {
+        let mut hasher = MultiHasher::with_backend(variant, backend)?;
+        file::read_file(path.as_ref(), &mut |data| hasher.update(data))?;
+        Ok(hasher.finalize())
+    }
+}
 
 impl MultiHash {
     #[cfg(all(feature = "generation", feature = "sha2"))]
     /// Generate a `MultiHash::Sha2_256` for the given data.
     pub fn generate_sha2_256(data: &[u8]) -> MultiHash {
-        sha2::generate_sha256(data)
+        MultiHash::generate(MultiHashVariant::Sha2_256, data).unwrap()
     }
 }
 
@@ -16,6 +263,405 @@ impl MultiHash {
     #[cfg(all(feature = "generation", feature = "sha2"))]
     /// Generate a `MultiHash::Sha2_512` for the given data.
     pub fn generate_sha2_512(data: &[u8]) -> MultiHash {
-        sha2::generate_sha512(data)
+        MultiHash::generate(MultiHashVariant::Sha2_512, data).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use { MultiHash, MultiHasher, MultiHashVariant };
+    use backend::{ HashBackend, HashState };
+
+    #[test]
+    fn unsupported() {
+        assert!(MultiHasher::new(MultiHashVariant::Identity).is_err());
+    }
+
+    struct NoBackend;
+
+    impl HashBackend for NoBackend {
+        fn new_state(&self, _variant: MultiHashVariant, _length: usize) -> Option<Box<dyn HashState>> {
+            None
+        }
+    }
+
+    #[test]
+    fn backend_unsupported() {
+        assert!(MultiHasher::with_backend(MultiHashVariant::Sha2_256, &NoBackend).is_err());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn backend_reader() {
+        use error::generation::ErrorKind;
+        use MultiHasherSet;
+        let variants = [MultiHashVariant::Sha2_256];
+        assert!(MultiHash::generate_from_reader(MultiHashVariant::Sha2_256, &b"abc"[..]).is_ok());
+        assert!(MultiHash::generate_from_reader_with_backend(MultiHashVariant::Sha2_256, &b"abc"[..], &NoBackend).is_err());
+        assert!(MultiHash::generate_all_from_reader_with_backend(&variants, &b"abc"[..], &NoBackend).is_err());
+        match *MultiHash::generate_file_with_backend("missing", MultiHashVariant::Sha2_256, &NoBackend).unwrap_err().kind() {
+            ErrorKind::UnsupportedVariant(MultiHashVariant::Sha2_256) => (),
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+        assert!(MultiHasherSet::with_backend(&variants, &NoBackend).is_err());
+    }
+
+    #[cfg(all(feature = "sha2", feature = "digest"))]
+    #[test]
+    fn digest_state() {
+        use backend::{ DefaultBackend, DigestState };
+        use rust_sha2::{ Digest, Sha256 };
+
+        struct DigestBackend;
+
+        impl HashBackend for DigestBackend {
+            fn new_state(&self, variant: MultiHashVariant, length: usize) -> Option<Box<dyn HashState>> {
+                match variant {
+                    MultiHashVariant::Sha2_256 => Some(Box::new(DigestState(Sha256::new()))),
+                    _ => DefaultBackend.new_state(variant, length),
+                }
+            }
+        }
+
+        let mut hasher = MultiHasher::with_backend(MultiHashVariant::Sha2_256, &DigestBackend).unwrap();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize(), MultiHash::generate_sha2_256(b"abc"));
+    }
+
+    #[test]
+    fn generate_unsupported() {
+        assert!(MultiHash::generate(MultiHashVariant::Identity, b"").is_err());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn generate_sha2() {
+        use test_vectors::SHA2_256_ABC;
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha2_256, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha2_256, &SHA2_256_ABC).unwrap());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha2_512, b"abc").unwrap(),
+            MultiHash::generate_sha2_512(b"abc"));
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn generate_sha2_224() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha2_224, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha2_224, &[
+                0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22,
+                0x86, 0x42, 0xa4, 0x77, 0xbd, 0xa2, 0x55, 0xb3,
+                0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7,
+                0xe3, 0x6c, 0x9d, 0xa7,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn generate_sha2_512_224() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha2_512_224, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha2_512_224, &[
+                0x46, 0x34, 0x27, 0x0f, 0x70, 0x7b, 0x6a, 0x54,
+                0xda, 0xae, 0x75, 0x30, 0x46, 0x08, 0x42, 0xe2,
+                0x0e, 0x37, 0xed, 0x26, 0x5c, 0xee, 0xe9, 0xa4,
+                0x3e, 0x89, 0x24, 0xaa,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn generate_sha2_384() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha2_384, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha2_384, &[
+                0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b,
+                0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6, 0x50, 0x07,
+                0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63,
+                0x1a, 0x8b, 0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed,
+                0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23,
+                0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn generate_sha2_512_256() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha2_512_256, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha2_512_256, &[
+                0x53, 0x04, 0x8e, 0x26, 0x81, 0x94, 0x1e, 0xf9,
+                0x9b, 0x2e, 0x29, 0xb7, 0x6b, 0x4c, 0x7d, 0xab,
+                0xe4, 0xc2, 0xd0, 0xc6, 0x34, 0xfc, 0x6d, 0x46,
+                0xe0, 0xe2, 0xf1, 0x31, 0x07, 0xe7, 0xaf, 0x23,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn generate_dbl_sha2_256() {
+        let once = MultiHash::generate_sha2_256(b"abc");
+        let twice = MultiHash::generate_sha2_256(once.digest());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::DblSha2_256, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::DblSha2_256, twice.digest()).unwrap());
+    }
+
+    #[cfg(feature = "ripemd")]
+    #[test]
+    fn generate_ripemd160() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Ripemd160, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Ripemd160, &[
+                0x8e, 0xb2, 0x08, 0xf7, 0xe0, 0x5d, 0x98, 0x7a, 0x9b, 0x04,
+                0x4a, 0x8e, 0x98, 0xc6, 0xb0, 0x87, 0xf1, 0x5a, 0x0b, 0xfc,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "md4")]
+    #[test]
+    fn generate_md4() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Md4, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Md4, &[
+                0xa4, 0x48, 0x01, 0x7a, 0xaf, 0x21, 0xd8, 0x52,
+                0x5f, 0xc1, 0x0a, 0xe8, 0x7a, 0xa6, 0x72, 0x9d,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "md5")]
+    #[test]
+    fn generate_md5() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Md5, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Md5, &[
+                0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0,
+                0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1, 0x7f, 0x72,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "murmur3")]
+    #[test]
+    fn generate_murmur3() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Murmur3_32, b"hello").unwrap(),
+            MultiHash::new(MultiHashVariant::Murmur3_32, &[0x24, 0x8b, 0xfa, 0x47]).unwrap());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Murmur3_32, b"The quick brown fox jumps over the lazy dog").unwrap(),
+            MultiHash::new(MultiHashVariant::Murmur3_32, &[0x2e, 0x4f, 0xf7, 0x23]).unwrap());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Murmur3X64_64, b"").unwrap(),
+            MultiHash::new(MultiHashVariant::Murmur3X64_64, &[0; 8]).unwrap());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Murmur3X64_64, b"hello").unwrap(),
+            MultiHash::new(MultiHashVariant::Murmur3X64_64, &[
+                0xcb, 0xd8, 0xa7, 0xb3, 0x41, 0xbd, 0x9b, 0x02,
+            ]).unwrap());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Murmur3X64_64, b"The quick brown fox jumps over the lazy dog").unwrap(),
+            MultiHash::new(MultiHashVariant::Murmur3X64_64, &[
+                0xe3, 0x4b, 0xbc, 0x7b, 0xbc, 0x07, 0x1b, 0x6c,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "xxhash")]
+    #[test]
+    fn generate_xxhash() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Xxh32, b"").unwrap().digest(),
+            &[0x02, 0xcc, 0x5d, 0x05]);
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Xxh64, b"").unwrap().digest(),
+            &[0xef, 0x46, 0xdb, 0x37, 0x51, 0xd8, 0xe9, 0x99]);
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Xxh3_64, b"").unwrap().digest(),
+            &[0x2d, 0x06, 0x80, 0x05, 0x38, 0xd3, 0x94, 0xc2]);
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Xxh3_128, b"").unwrap().digest(),
+            &[
+                0x99, 0xaa, 0x06, 0xd3, 0x01, 0x47, 0x98, 0xd8,
+                0x60, 0x01, 0xc3, 0x24, 0x46, 0x8d, 0x49, 0x7f,
+            ]);
+    }
+
+    #[cfg(feature = "sha1")]
+    #[test]
+    fn generate_sha1() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha1, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha1, &[
+                0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e,
+                0x25, 0x71, 0x78, 0x50, 0xc2, 0x6c, 0x9c, 0xd0, 0xd8, 0x9d,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn generate_sha3_256() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Sha3_256, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Sha3_256, &[
+                0x3a, 0x98, 0x5d, 0xa7, 0x4f, 0xe2, 0x25, 0xb2,
+                0x04, 0x5c, 0x17, 0x2d, 0x6b, 0xd3, 0x90, 0xbd,
+                0x85, 0x5f, 0x08, 0x6e, 0x3e, 0x9d, 0x52, 0x5b,
+                0x46, 0xbf, 0xe2, 0x45, 0x11, 0x43, 0x15, 0x32,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn generate_keccak256() {
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Keccak256, b"").unwrap(),
+            MultiHash::new(MultiHashVariant::Keccak256, &[
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c,
+                0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+                0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b,
+                0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn generate_shake128() {
+        assert_eq!(
+            MultiHash::generate_with_length(MultiHashVariant::Shake128, b"", 16).unwrap(),
+            MultiHash::new(MultiHashVariant::Shake128, &[
+                0x7f, 0x9c, 0x2b, 0xa4, 0xe8, 0x8f, 0x82, 0x7d,
+                0x61, 0x60, 0x45, 0x50, 0x76, 0x05, 0x85, 0x3e,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn shake_default_lengths() {
+        assert_eq!(MultiHash::generate(MultiHashVariant::Shake128, b"").unwrap().len(), 32);
+        assert_eq!(MultiHash::generate(MultiHashVariant::Shake256, b"").unwrap().len(), 64);
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn shake_zero_length() {
+        assert!(MultiHasher::with_length(MultiHashVariant::Shake128, 0).is_err());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn fixed_wrong_length() {
+        assert!(MultiHasher::with_length(MultiHashVariant::Sha2_256, 0).is_err());
+        assert!(MultiHasher::with_length(MultiHashVariant::Sha2_256, 33).is_err());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn truncated() {
+        let full = MultiHash::generate_sha2_256(b"abc");
+        let truncated = MultiHash::generate_with_length(MultiHashVariant::Sha2_256, b"abc", 20).unwrap();
+        assert_eq!(truncated.variant(), MultiHashVariant::Sha2_256);
+        assert_eq!(truncated.digest(), &full.digest()[..20]);
+        assert_eq!(
+            MultiHash::generate_with_length(MultiHashVariant::Sha2_256, b"abc", 32).unwrap(),
+            full);
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn truncated_blake2b() {
+        let variant = MultiHashVariant::Blake2BSized { length: 32 };
+        let full = MultiHash::generate(variant, b"abc").unwrap();
+        let truncated = MultiHash::generate_with_length(variant, b"abc", 8).unwrap();
+        assert_eq!(truncated.digest(), &full.digest()[..8]);
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn generate_blake2b_256() {
+        let variant = MultiHashVariant::Blake2BSized { length: 32 };
+        assert_eq!(
+            MultiHash::generate(variant, b"abc").unwrap(),
+            MultiHash::new(variant, &[
+                0xbd, 0xdd, 0x81, 0x3c, 0x63, 0x42, 0x39, 0x72,
+                0x31, 0x71, 0xef, 0x3f, 0xee, 0x98, 0x57, 0x9b,
+                0x94, 0x96, 0x4e, 0x3b, 0xb1, 0xcb, 0x3e, 0x42,
+                0x72, 0x62, 0xc8, 0xc0, 0x68, 0xd5, 0x23, 0x19,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn generate_blake2s_256() {
+        let variant = MultiHashVariant::Blake2SSized { length: 32 };
+        assert_eq!(
+            MultiHash::generate(variant, b"abc").unwrap(),
+            MultiHash::new(variant, &[
+                0x50, 0x8c, 0x5e, 0x8c, 0x32, 0x7c, 0x14, 0xe2,
+                0xe1, 0xa7, 0x2b, 0xa3, 0x4e, 0xeb, 0x45, 0x2f,
+                0x37, 0x45, 0x8b, 0x20, 0x9e, 0xd6, 0x3a, 0x29,
+                0x4d, 0x99, 0x9b, 0x4c, 0x86, 0x67, 0x59, 0x82,
+            ]).unwrap());
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Blake2S, b"abc").unwrap().digest(),
+            MultiHash::generate(variant, b"abc").unwrap().digest());
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn blake2_invalid_length() {
+        assert!(MultiHash::generate(MultiHashVariant::Blake2SSized { length: 40 }, b"abc").is_err());
+        assert!(MultiHash::generate(MultiHashVariant::Blake2BSized { length: 0 }, b"abc").is_err());
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn generate_blake2b_64() {
+        let variant = MultiHashVariant::from_code(0xb208).unwrap();
+        assert_eq!(
+            MultiHash::generate(variant, b"abc").unwrap(),
+            MultiHash::new(variant, &[
+                0xd8, 0xbb, 0x14, 0xd8, 0x33, 0xd5, 0x95, 0x59,
+            ]).unwrap());
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn generate_blake3() {
+        let expected = [
+            0x64, 0x37, 0xb3, 0xac, 0x38, 0x46, 0x51, 0x33,
+            0xff, 0xb6, 0x3b, 0x75, 0x27, 0x3a, 0x8d, 0xb5,
+            0x48, 0xc5, 0x58, 0x46, 0x5d, 0x79, 0xdb, 0x03,
+            0xfd, 0x35, 0x9c, 0x6c, 0xd5, 0xbd, 0x9d, 0x85,
+        ];
+        assert_eq!(
+            MultiHash::generate(MultiHashVariant::Blake3, b"abc").unwrap(),
+            MultiHash::new(MultiHashVariant::Blake3, &expected).unwrap());
+        let long = MultiHash::generate_with_length(MultiHashVariant::Blake3, b"abc", 64).unwrap();
+        assert_eq!(long.len(), 64);
+        assert_eq!(&long.digest()[..32], &expected[..]);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn sha2_256_chunked() {
+        let mut hasher = MultiHasher::new(MultiHashVariant::Sha2_256).unwrap();
+        for chunk in b"the quick brown fox".chunks(3) {
+            hasher.update(chunk);
+        }
+        assert_eq!(
+            hasher.finalize(),
+            MultiHash::generate_sha2_256(b"the quick brown fox"));
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn sha2_512_chunked() {
+        let mut hasher = MultiHasher::new(MultiHashVariant::Sha2_512).unwrap();
+        for chunk in b"the quick brown fox".chunks(5) {
+            hasher.update(chunk);
+        }
+        assert_eq!(
+            hasher.finalize(),
+            MultiHash::generate_sha2_512(b"the quick brown fox"));
     }
 }
@@ -0,0 +1,108 @@
+use std::io;
+
+use error;
+use generation::MultiHasher;
+use { MultiHash, MultiHashVariant };
+
+/// Wraps a reader, generating a `MultiHash` of all data read through it once
+/// the wrapped reader reaches EOF.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "sha2")] {
+/// use std::io::Read;
+/// use mhash::{ HashingReader, MultiHash, MultiHashVariant };
+/// let mut reader = HashingReader::new(&b"hello world"[..], MultiHashVariant::Sha2_256).unwrap();
+/// let mut buffer = Vec::new();
+/// reader.read_to_end(&mut buffer).unwrap();
+/// assert_eq!(
+///     reader.multihash(),
+///     Some(&MultiHash::generate_sha2_256(b"hello world")));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Option<MultiHasher>,
+    multihash: Option<MultiHash>,
+}
+
+impl<R: io::Read> HashingReader<R> {
+    /// Wrap the reader, hashing the data read with the specified variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator for the variant
+    /// available, see `MultiHasher::new`.
+    pub fn new(inner: R, variant: MultiHashVariant) -> error::generation::Result<HashingReader<R>> {
+        Ok(HashingReader::with_hasher(inner, MultiHasher::new(variant)?))
+    }
+
+    /// Wrap the reader, feeding the data read to the given hasher.
+    pub fn with_hasher(inner: R, hasher: MultiHasher) -> HashingReader<R> {
+        HashingReader { inner: inner, hasher: Some(hasher), multihash: None }
+    }
+
+    /// A reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The `MultiHash` of all data read, available once the wrapped reader
+    /// has reached EOF. Any data the wrapped reader returns after EOF is not
+    /// included.
+    pub fn multihash(&self) -> Option<&MultiHash> {
+        self.multihash.as_ref()
+    }
+
+    /// Unwrap the reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: io::Read> io::Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read > 0 {
+            if let Some(ref mut hasher) = self.hasher {
+                hasher.update(&buf[..read]);
+            }
+        } else if !buf.is_empty() {
+            if let Some(hasher) = self.hasher.take() {
+                self.multihash = Some(hasher.finalize());
+            }
+        }
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use { HashingReader, MultiHashVariant };
+
+    #[test]
+    fn unsupported() {
+        assert!(HashingReader::new(&b""[..], MultiHashVariant::Identity).is_err());
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn eof() {
+        use std::io::Read;
+        use MultiHash;
+
+        let mut reader = HashingReader::new(&b"hello world"[..], MultiHashVariant::Blake3).unwrap();
+        let mut buffer = [0; 6];
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(reader.read(&mut []).unwrap(), 0);
+        assert_eq!(reader.multihash(), None);
+        assert_eq!(reader.read(&mut buffer).unwrap(), 5);
+        assert_eq!(reader.multihash(), None);
+        assert_eq!(reader.read(&mut buffer).unwrap(), 0);
+        assert_eq!(
+            reader.multihash(),
+            Some(&MultiHash::generate(MultiHashVariant::Blake3, b"hello world").unwrap()));
+    }
+}
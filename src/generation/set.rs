@@ -0,0 +1,221 @@
+use std::fmt;
+use std::io;
+
+use backend::{ DefaultBackend, HashBackend };
+use error;
+use generation::MultiHasher;
+use { MultiHash, MultiHashVariant };
+
+/// Generates a `MultiHash` of each of a set of variants in a single pass over
+/// the data.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(feature = "sha1", feature = "sha2"))] {
+/// use mhash::{ MultiHash, MultiHasherSet, MultiHashVariant };
+/// let variants = [MultiHashVariant::Sha2_256, MultiHashVariant::Sha1];
+/// let mut hashers = MultiHasherSet::new(&variants).unwrap();
+/// hashers.update(b"hello ");
+/// hashers.update(b"world");
+/// let multihashes = hashers.finalize();
+/// assert_eq!(multihashes[0], MultiHash::generate_sha2_256(b"hello world"));
+/// assert_eq!(multihashes[1].variant(), MultiHashVariant::Sha1);
+/// # }
+/// ```
+pub struct MultiHasherSet {
+    hashers: Vec<MultiHasher>,
+}
+
+impl MultiHasherSet {
+    /// Create a new set of hashers, one for each of the specified variants.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator available for
+    /// any of the variants, see `MultiHasher::new`.
+    pub fn new(variants: &[MultiHashVariant]) -> error::generation::Result<MultiHasherSet> {
+        MultiHasherSet::with_backend(variants, &DefaultBackend)
+    }
+
+    /// Create a new set of hashers, one for each of the specified variants,
+    /// using the hash implementations provided by the given backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if the backend has no implementation of
+    /// any of the variants.
+    pub fn with_backend(variants: &[MultiHashVariant], backend: &dyn HashBackend) -> error::generation::Result<MultiHasherSet> {
+        let hashers = variants.iter()
+            .map(|&variant| MultiHasher::with_backend(variant, backend))
+            .collect::<error::generation::Result<_>>()?;
+        Ok(MultiHasherSet::from_hashers(hashers))
+    }
+
+    /// Create a set from already constructed hashers, e.g. to mix hashers
+    /// using different lengths or backends.
+    pub fn from_hashers(hashers: Vec<MultiHasher>) -> MultiHasherSet {
+        MultiHasherSet { hashers: hashers }
+    }
+
+    /// The variants this set will generate, in order.
+    pub fn variants(&self) -> Vec<MultiHashVariant> {
+        self.hashers.iter().map(MultiHasher::variant).collect()
+    }
+
+    /// Feed the next chunk of data into every hasher in the set.
+    pub fn update(&mut self, data: &[u8]) {
+        for hasher in &mut self.hashers {
+            hasher.update(data);
+        }
+    }
+
+    /// Consume the set and return the `MultiHash` of all data fed into it for
+    /// each variant, in the same order the variants were given.
+    pub fn finalize(self) -> Vec<MultiHash> {
+        self.hashers.into_iter().map(MultiHasher::finalize).collect()
+    }
+}
+
+impl io::Write for MultiHasherSet {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for MultiHasherSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.hashers.iter().map(|hasher| hasher.variant().name()))
+            .finish()
+    }
+}
+
+impl MultiHash {
+    /// Generate a `MultiHash` of each of the specified variants for the given
+    /// data, in the same order the variants were given.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator available for
+    /// any of the variants.
+    pub fn generate_all(variants: &[MultiHashVariant], data: &[u8]) -> error::generation::Result<Vec<MultiHash>> {
+        let mut hashers = MultiHasherSet::new(variants)?;
+        hashers.update(data);
+        Ok(hashers.finalize())
+    }
+
+    /// Generate a `MultiHash` of the specified variant for all data read from
+    /// the given reader.
+    ///
+    /// # Errors
+    ///
+    /// As for `generate`, and additionally returns `Io` if reading fails.
+    pub fn generate_from_reader<R: io::Read>(variant: MultiHashVariant, reader: R) -> error::generation::Result<MultiHash> {
+        MultiHash::generate_from_reader_with_backend(variant, reader, &DefaultBackend)
+    }
+
+    /// As for `generate_from_reader`, except the hash implementation is
+    /// provided by the given backend.
+    pub fn generate_from_reader_with_backend<R: io::Read>(variant: MultiHashVariant, mut reader: R, backend: &dyn HashBackend) -> error::generation::Result<MultiHash> {
+        let mut hasher = MultiHasher::with_backend(variant, backend)?;
+        let _ = io::copy(&mut reader, &mut hasher)?;
+        Ok(hasher.finalize())
+    }
+
+    /// Generate a `MultiHash` of each of the specified variants for all data
+    /// read from the given reader, reading through the data only once.
+    ///
+    /// # Errors
+    ///
+    /// As for `generate_all`, and additionally returns `Io` if reading fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "sha1", feature = "sha2"))] {
+    /// use mhash::{ MultiHash, MultiHashVariant };
+    /// let variants = [MultiHashVariant::Sha2_256, MultiHashVariant::Sha1];
+    /// let multihashes = MultiHash::generate_all_from_reader(
+    ///     &variants, &b"hello world"[..]).unwrap();
+    /// assert_eq!(
+    ///     multihashes,
+    ///     MultiHash::generate_all(&variants, b"hello world").unwrap());
+    /// # }
+    /// ```
+    pub fn generate_all_from_reader<R: io::Read>(variants: &[MultiHashVariant], reader: R) -> error::generation::Result<Vec<MultiHash>> {
+        MultiHash::generate_all_from_reader_with_backend(variants, reader, &DefaultBackend)
+    }
+
+    /// As for `generate_all_from_reader`, except the hash implementations are
+    /// provided by the given backend.
+    pub fn generate_all_from_reader_with_backend<R: io::Read>(variants: &[MultiHashVariant], mut reader: R, backend: &dyn HashBackend) -> error::generation::Result<Vec<MultiHash>> {
+        let mut hashers = MultiHasherSet::with_backend(variants, backend)?;
+        let _ = io::copy(&mut reader, &mut hashers)?;
+        Ok(hashers.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use { MultiHasherSet, MultiHashVariant };
+
+    #[test]
+    fn empty() {
+        let hashers = MultiHasherSet::new(&[]).unwrap();
+        assert_eq!(hashers.finalize(), vec![]);
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(MultiHasherSet::new(&[MultiHashVariant::Identity]).is_err());
+    }
+
+    #[cfg(all(feature = "sha1", feature = "sha2", feature = "blake2"))]
+    #[test]
+    fn single_pass() {
+        use MultiHash;
+        let variants = [
+            MultiHashVariant::Sha2_256,
+            MultiHashVariant::Blake2BSized { length: 32 },
+            MultiHashVariant::Sha1,
+        ];
+        let mut hashers = MultiHasherSet::new(&variants).unwrap();
+        assert_eq!(hashers.variants(), variants.to_vec());
+        hashers.update(b"a");
+        hashers.update(b"bc");
+        let expected = variants.iter()
+            .map(|&variant| MultiHash::generate(variant, b"abc").unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(hashers.finalize(), expected);
+        assert_eq!(MultiHash::generate_all(&variants, b"abc").unwrap(), expected);
+        assert_eq!(MultiHash::generate_all_from_reader(&variants, &b"abc"[..]).unwrap(), expected);
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn from_hashers() {
+        use MultiHasher;
+        let hashers = MultiHasherSet::from_hashers(vec![
+            MultiHasher::new(MultiHashVariant::Sha3_256).unwrap(),
+            MultiHasher::with_length(MultiHashVariant::Shake128, 16).unwrap(),
+        ]);
+        let multihashes = hashers.finalize();
+        assert_eq!(multihashes[0].len(), 32);
+        assert_eq!(multihashes[1].len(), 16);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn from_reader() {
+        use MultiHash;
+        assert_eq!(
+            MultiHash::generate_from_reader(MultiHashVariant::Sha2_256, &b"abc"[..]).unwrap(),
+            MultiHash::generate_sha2_256(b"abc"));
+    }
+}
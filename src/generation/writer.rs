@@ -0,0 +1,109 @@
+use std::io;
+
+use error;
+use generation::MultiHasher;
+use { MultiHash, MultiHashVariant };
+
+/// Wraps a writer, generating a `MultiHash` of all data written through it.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "sha2")] {
+/// use std::io::Write;
+/// use mhash::{ HashingWriter, MultiHash, MultiHashVariant };
+/// let mut writer = HashingWriter::new(Vec::new(), MultiHashVariant::Sha2_256).unwrap();
+/// writer.write_all(b"hello world").unwrap();
+/// let (buffer, multihash) = writer.finish();
+/// assert_eq!(buffer, b"hello world");
+/// assert_eq!(multihash, MultiHash::generate_sha2_256(b"hello world"));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: MultiHasher,
+}
+
+impl<W: io::Write> HashingWriter<W> {
+    /// Wrap the writer, hashing the data written with the specified variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedVariant` if there is no generator for the variant
+    /// available, see `MultiHasher::new`.
+    pub fn new(inner: W, variant: MultiHashVariant) -> error::generation::Result<HashingWriter<W>> {
+        Ok(HashingWriter::with_hasher(inner, MultiHasher::new(variant)?))
+    }
+
+    /// Wrap the writer, feeding the data written to the given hasher.
+    pub fn with_hasher(inner: W, hasher: MultiHasher) -> HashingWriter<W> {
+        HashingWriter { inner: inner, hasher: hasher }
+    }
+
+    /// A reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Unwrap the writer, returning it along with the `MultiHash` of all data
+    /// written through it. The wrapped writer is not flushed.
+    pub fn finish(self) -> (W, MultiHash) {
+        (self.inner, self.hasher.finalize())
+    }
+}
+
+impl<W: io::Write> io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use { HashingWriter, MultiHashVariant };
+
+    #[test]
+    fn unsupported() {
+        assert!(HashingWriter::new(Vec::new(), MultiHashVariant::Identity).is_err());
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn short_writes() {
+        use std::io::{ self, Write };
+        use MultiHasher;
+
+        /// Accepts at most 3 bytes per write.
+        struct Short(Vec<u8>);
+
+        impl Write for Short {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let len = if buf.len() < 3 { buf.len() } else { 3 };
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let hasher = MultiHasher::with_length(MultiHashVariant::Shake256, 32).unwrap();
+        let mut writer = HashingWriter::with_hasher(Short(Vec::new()), hasher);
+        assert_eq!(writer.write(b"hello world").unwrap(), 3);
+        writer.write_all(b"lo world").unwrap();
+        let (inner, multihash) = writer.finish();
+
+        let mut expected = MultiHasher::with_length(MultiHashVariant::Shake256, 32).unwrap();
+        expected.update(b"hello world");
+        assert_eq!(inner.0, b"hello world");
+        assert_eq!(multihash, expected.finalize());
+    }
+}
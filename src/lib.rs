@@ -16,12 +16,47 @@
 //! [multihash]: https://github.com/multiformats/multihash
 //! [ipfs]: https://ipfs.io
 
-#[cfg(feature = "sha2")]
+#[cfg(feature = "sha1")]
+extern crate sha1;
+
+#[cfg(feature = "sodiumoxide")]
 extern crate sodiumoxide;
 
+#[cfg(feature = "sha2")]
+extern crate rust_sha2;
+
+#[cfg(feature = "blake2")]
+extern crate rust_blake2;
+
+#[cfg(feature = "blake3")]
+extern crate blake3;
+
+#[cfg(feature = "ripemd")]
+extern crate ripemd;
+
+#[cfg(feature = "md4")]
+extern crate md4;
+
+#[cfg(feature = "md5")]
+extern crate md5;
+
+#[cfg(feature = "xxhash")]
+extern crate xxhash;
+
+#[cfg(feature = "sha3")]
+extern crate sha3;
+
 #[cfg(feature = "str")]
 extern crate bs58;
 
+#[cfg(feature = "digest")]
+extern crate digest;
+
+#[cfg(feature = "memmap2")]
+extern crate memmap2;
+
+extern crate constant_time_eq;
+
 #[macro_use]
 extern crate error_chain;
 
@@ -30,7 +65,9 @@ extern crate varmint;
 
 extern crate smallvec;
 
+pub mod backend;
 pub mod error;
+pub mod registry;
 mod multihash;
 mod variant;
 
@@ -50,6 +87,12 @@ mod validation;
 #[cfg(feature = "generation")]
 mod generation;
 
+#[cfg(any(feature = "generation", feature = "validation"))]
+mod file;
+
+#[cfg(test)]
+mod test_vectors;
+
 pub use multihash::MultiHash;
 pub use variant::MultiHashVariant;
 
@@ -57,3 +100,9 @@ pub use variant::MultiHashVariant;
 pub use read::ReadMultiHash;
 #[cfg(feature = "io")]
 pub use write::WriteMultiHash;
+
+#[cfg(feature = "validation")]
+pub use validation::{ BatchSummary, BatchVerification, VerifyingReader };
+
+#[cfg(feature = "generation")]
+pub use generation::{ HashingReader, HashingWriter, MultiHasher, MultiHasherSet };
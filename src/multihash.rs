@@ -1,5 +1,6 @@
 use std::fmt;
 
+use constant_time_eq::constant_time_eq;
 use smallvec::SmallVec;
 
 use error;
@@ -21,6 +22,14 @@ impl MultiHash {
         Ok(MultiHash { variant: variant, digest: digest.into() })
     }
 
+    /// Create a new multihash without checking the length of the digest, for
+    /// digests whose length was checked when their hash state was created, as
+    /// an application specific variant's registration may since have changed.
+    #[cfg(any(feature = "generation", feature = "validation"))]
+    pub(crate) fn new_unchecked(variant: MultiHashVariant, digest: &[u8]) -> MultiHash {
+        MultiHash { variant: variant, digest: digest.into() }
+    }
+
     /// Create a new multihash with the specified code and digest, validates
     /// that the code is known or an application specific variant, and that the
     /// length is consistent with the multihash variant the code refers to.
@@ -53,6 +62,15 @@ impl MultiHash {
     pub fn digest(&self) -> &[u8] {
         &self.digest
     }
+
+    /// Compare this multihash with another in constant time with respect to
+    /// the digest contents, for use where one of them may be secret-derived.
+    ///
+    /// The variants and digest lengths are not considered secret, only the
+    /// time taken to compare the digests is independent of their contents.
+    pub fn ct_eq(&self, other: &MultiHash) -> bool {
+        self.variant == other.variant && constant_time_eq(&self.digest, &other.digest)
+    }
 }
 
 impl fmt::Debug for MultiHash {
@@ -66,3 +84,17 @@ impl fmt::Debug for MultiHash {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use { MultiHash, MultiHashVariant };
+
+    #[test]
+    fn ct_eq() {
+        let multihash = MultiHash::new(MultiHashVariant::Sha1, &[1; 20]).unwrap();
+        assert!(multihash.ct_eq(&MultiHash::new(MultiHashVariant::Sha1, &[1; 20]).unwrap()));
+        assert!(!multihash.ct_eq(&MultiHash::new(MultiHashVariant::Sha1, &[2; 20]).unwrap()));
+        assert!(!multihash.ct_eq(&MultiHash::new(MultiHashVariant::Sha1, &[1; 19]).unwrap()));
+        assert!(!multihash.ct_eq(&MultiHash::new(MultiHashVariant::Ripemd160, &[1; 20]).unwrap()));
+    }
+}
@@ -0,0 +1,221 @@
+//! A runtime registry giving application specific multihash variants a name,
+//! maximum length and hash implementation.
+//!
+//! Once a code has been registered `MultiHashVariant::name` and
+//! `MultiHashVariant::check_length` use the registered details, and the
+//! `DefaultBackend` uses the registered hash implementation for generating and
+//! validating multihashes of that variant.
+
+use std::collections::HashMap;
+use std::sync::{ Arc, LazyLock, RwLock };
+
+use backend::HashState;
+use error;
+
+struct Registration {
+    name: &'static str,
+    max_len: usize,
+    new_state: Arc<dyn Fn() -> Box<dyn HashState> + Send + Sync>,
+}
+
+static REGISTRY: LazyLock<RwLock<HashMap<usize, Registration>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Register an application specific code with the given name, maximum digest
+/// length and function creating a fresh hash state. Registering a code that is
+/// already registered replaces the existing registration.
+///
+/// # Errors
+///
+/// Returns `NotApplicationSpecific` if the code is outside the application
+/// specific range [0x0400, 0x040f].
+///
+/// # Examples
+///
+/// ```rust
+/// use mhash::MultiHashVariant;
+/// use mhash::backend::HashState;
+///
+/// struct Length(u32);
+///
+/// impl HashState for Length {
+///     fn update(&mut self, data: &[u8]) {
+///         self.0 += data.len() as u32;
+///     }
+///
+///     fn finalize(self: Box<Self>) -> Vec<u8> {
+///         self.0.to_be_bytes().to_vec()
+///     }
+/// }
+///
+/// mhash::registry::register(0x0402, "length", 4, || Box::new(Length(0))).unwrap();
+///
+/// let variant = MultiHashVariant::from_code(0x0402).unwrap();
+/// assert_eq!(variant.name(), "length");
+/// assert!(variant.check_length(5).is_err());
+/// ```
+pub fn register<F>(code: usize, name: &'static str, max_len: usize, new_state: F) -> error::creation::Result<()>
+    where F: Fn() -> Box<dyn HashState> + Send + Sync + 'static
+{
+    if code < 0x0400 || code > 0x040f {
+        return Err(error::creation::ErrorKind::NotApplicationSpecific(code).into());
+    }
+    let registration = Registration {
+        name: name,
+        max_len: max_len,
+        new_state: Arc::new(new_state),
+    };
+    let _ = REGISTRY.write().unwrap().insert(code, registration);
+    Ok(())
+}
+
+/// Remove the registration for an application specific code, returns whether
+/// there was one.
+pub fn unregister(code: usize) -> bool {
+    REGISTRY.write().unwrap().remove(&code).is_some()
+}
+
+/// The name registered for an application specific code.
+pub fn name(code: usize) -> Option<&'static str> {
+    REGISTRY.read().unwrap().get(&code).map(|registration| registration.name)
+}
+
+/// The maximum digest length registered for an application specific code.
+pub fn max_len(code: usize) -> Option<usize> {
+    REGISTRY.read().unwrap().get(&code).map(|registration| registration.max_len)
+}
+
+/// A fresh hash state from the implementation registered for an application
+/// specific code.
+pub fn new_state(code: usize) -> Option<Box<dyn HashState>> {
+    // Release the lock before calling the factory, it may use the registry
+    let new_state = REGISTRY.read().unwrap().get(&code).map(|registration| registration.new_state.clone());
+    new_state.map(|new_state| new_state())
+}
+
+#[cfg(test)]
+mod tests {
+    use backend::HashState;
+    use registry;
+    use MultiHashVariant;
+
+    struct Sum(u8);
+
+    impl HashState for Sum {
+        fn update(&mut self, data: &[u8]) {
+            for byte in data {
+                self.0 = self.0.wrapping_add(*byte);
+            }
+        }
+
+        fn finalize(self: Box<Self>) -> Vec<u8> {
+            vec![self.0]
+        }
+    }
+
+    #[test]
+    fn registered() {
+        registry::register(0x0400, "sum", 1, || Box::new(Sum(0))).unwrap();
+        let variant = MultiHashVariant::from_code(0x0400).unwrap();
+        assert_eq!(variant.name(), "sum");
+        assert_eq!(variant.max_len(), 1);
+        assert!(variant.check_length(2).is_err());
+        assert!(registry::unregister(0x0400));
+        assert_eq!(variant.name(), "app-specific");
+        assert_eq!(variant.max_len(), usize::max_value());
+    }
+
+    #[test]
+    fn not_application_specific() {
+        assert!(registry::register(0x12, "sum", 1, || Box::new(Sum(0))).is_err());
+    }
+
+    #[cfg(feature = "generation")]
+    #[test]
+    fn generate() {
+        use MultiHash;
+        registry::register(0x040f, "sum", 1, || Box::new(Sum(0))).unwrap();
+        let variant = MultiHashVariant::ApplicationSpecific { code: 0x040f };
+        assert_eq!(
+            MultiHash::generate(variant, &[1, 2, 3]).unwrap(),
+            MultiHash::new(variant, &[6]).unwrap());
+    }
+
+    #[test]
+    fn reentrant_factory() {
+        registry::register(0x040d, "sum", 1, || {
+            registry::register(0x040c, "sum", 1, || Box::new(Sum(0))).unwrap();
+            let _ = MultiHashVariant::ApplicationSpecific { code: 0x040d }.name();
+            Box::new(Sum(0))
+        }).unwrap();
+        assert!(registry::new_state(0x040d).is_some());
+        assert!(registry::new_state(0x040c).is_some());
+    }
+
+    /// Produces a fixed length digest of zeros.
+    #[cfg(any(feature = "generation", feature = "validation"))]
+    struct Zeros(usize);
+
+    #[cfg(any(feature = "generation", feature = "validation"))]
+    impl HashState for Zeros {
+        fn update(&mut self, _data: &[u8]) {
+        }
+
+        fn finalize(self: Box<Self>) -> Vec<u8> {
+            vec![0; self.0]
+        }
+    }
+
+    #[cfg(feature = "generation")]
+    #[test]
+    fn reregistered_generate() {
+        use MultiHasher;
+        registry::register(0x0405, "zeros", 32, || Box::new(Zeros(32))).unwrap();
+        let variant = MultiHashVariant::ApplicationSpecific { code: 0x0405 };
+        let hasher = MultiHasher::new(variant).unwrap();
+        registry::register(0x0405, "zeros", 16, || Box::new(Zeros(16))).unwrap();
+        assert_eq!(hasher.finalize().digest(), &[0; 32][..]);
+    }
+
+    #[cfg(feature = "generation")]
+    #[test]
+    fn short_digest() {
+        use MultiHash;
+        registry::register(0x0407, "sum", 32, || Box::new(Sum(0))).unwrap();
+        let variant = MultiHashVariant::ApplicationSpecific { code: 0x0407 };
+        let multihash = MultiHash::generate_with_length(variant, &[1, 2, 3], 20).unwrap();
+        assert_eq!(multihash.digest(), &[6]);
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn reregistered_validate() {
+        use MultiHash;
+        use error::validation::ErrorKind;
+        registry::register(0x0406, "zeros", 32, || Box::new(Zeros(32))).unwrap();
+        let variant = MultiHashVariant::ApplicationSpecific { code: 0x0406 };
+        let multihash = MultiHash::new(variant, &[1; 32]).unwrap();
+        registry::register(0x0406, "zeros", 16, || Box::new(Zeros(32))).unwrap();
+        match *multihash.verify(b"abc").unwrap_err().kind() {
+            ErrorKind::Mismatch(ref mismatch) => assert_eq!(mismatch.computed.digest(), &[0; 32][..]),
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn validate() {
+        use MultiHash;
+        use error::validation::ErrorKind;
+        registry::register(0x040e, "sum", 2, || Box::new(Sum(0))).unwrap();
+        let variant = MultiHashVariant::ApplicationSpecific { code: 0x040e };
+        let multihash = MultiHash::new(variant, &[6]).unwrap();
+        assert!(multihash.validate(&[1, 2, 3]).unwrap().unwrap());
+        assert!(!multihash.validate(&[1, 2, 4]).unwrap().unwrap());
+        assert!(multihash.verify(&[1, 2, 3]).is_ok());
+        let too_long = MultiHash::new(variant, &[6, 0]).unwrap();
+        match *too_long.verify(&[1, 2, 3]).unwrap_err().kind() {
+            ErrorKind::DigestTooLong(_, 2, 1) => (),
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+}
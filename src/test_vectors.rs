@@ -0,0 +1,10 @@
+//! Digests shared between the tests of several modules.
+
+/// The SHA-256 digest of "abc", from FIPS 180-2.
+#[allow(dead_code)] // Will be unused if sha2 is not active
+pub const SHA2_256_ABC: [u8; 32] = [
+    0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea,
+    0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+    0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c,
+    0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+];
@@ -0,0 +1,271 @@
+use std::cmp;
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread;
+
+use backend::{ DefaultBackend, HashBackend };
+use error;
+use MultiHash;
+
+/// The results of verifying a batch of multihashes, see
+/// `MultiHash::verify_batch`.
+///
+/// This holds a result for every item, for large batches use
+/// `MultiHash::verify_batch_each` to handle each result as it completes.
+#[derive(Debug)]
+pub struct BatchVerification {
+    /// The result of verifying each item, in the same order the items were
+    /// given.
+    pub results: Vec<error::validation::Result<()>>,
+    /// Counts of how many items passed, failed or were unsupported.
+    pub summary: BatchSummary,
+}
+
+/// Counts of the outcomes of a batch verification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Items whose data matched their multihash.
+    pub passed: usize,
+    /// Items whose data did not match their multihash, or could not be read.
+    pub failed: usize,
+    /// Items with no validator available for their variant.
+    pub unsupported: usize,
+}
+
+impl BatchSummary {
+    fn add(&mut self, result: &error::validation::Result<()>) {
+        match *result {
+            Ok(()) => self.passed += 1,
+            Err(ref err) => match *err.kind() {
+                error::validation::ErrorKind::NoValidator(_) => self.unsupported += 1,
+                _ => self.failed += 1,
+            },
+        }
+    }
+}
+
+impl fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} passed, {} failed, {} unsupported", self.passed, self.failed, self.unsupported)
+    }
+}
+
+impl MultiHash {
+    /// Verify each multihash against the data read from its paired reader,
+    /// spreading the work across one thread per available CPU.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "generation", feature = "sha2"))] {
+    /// use mhash::MultiHash;
+    /// let blocks: Vec<&[u8]> = vec![b"hello", b"world"];
+    /// let items = blocks.iter().map(|block| (MultiHash::generate_sha2_256(block), *block));
+    /// let verification = MultiHash::verify_batch(items);
+    /// assert_eq!(verification.summary.passed, 2);
+    /// # }
+    /// ```
+    pub fn verify_batch<I, R>(items: I) -> BatchVerification
+        where I: IntoIterator<Item=(MultiHash, R)>, I::IntoIter: Send, R: io::Read + Send
+    {
+        let threads = thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1);
+        MultiHash::verify_batch_with_threads(items, threads)
+    }
+
+    /// As for `verify_batch`, except using the given number of threads, at
+    /// least one thread is always used.
+    pub fn verify_batch_with_threads<I, R>(items: I, threads: usize) -> BatchVerification
+        where I: IntoIterator<Item=(MultiHash, R)>, I::IntoIter: Send, R: io::Read + Send
+    {
+        MultiHash::verify_batch_with_backend(items, threads, &DefaultBackend)
+    }
+
+    /// As for `verify_batch_with_threads`, except the hash implementations
+    /// are provided by the given backend.
+    pub fn verify_batch_with_backend<I, R>(items: I, threads: usize, backend: &(dyn HashBackend + Sync)) -> BatchVerification
+        where I: IntoIterator<Item=(MultiHash, R)>, I::IntoIter: Send, R: io::Read + Send
+    {
+        let mut results = Vec::new();
+        let summary = MultiHash::verify_batch_each_with_backend(items, threads, backend, |index, result| {
+            if index >= results.len() {
+                results.resize_with(index + 1, || None);
+            }
+            results[index] = Some(result);
+        });
+
+        BatchVerification {
+            results: results.into_iter().map(Option::unwrap).collect(),
+            summary: summary,
+        }
+    }
+
+    /// Verify each multihash against the data read from its paired reader
+    /// using the given number of threads (at least one), passing each result
+    /// to `each` along with the index of its item instead of collecting them.
+    ///
+    /// Results are passed on the calling thread in the order they complete,
+    /// which may differ from the order of the items. Only a bounded number of
+    /// results are buffered, so memory use does not grow with the size of the
+    /// batch; pass a closure that ignores its arguments to only get the
+    /// summary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "generation", feature = "sha2"))] {
+    /// use mhash::MultiHash;
+    /// let blocks: Vec<&[u8]> = vec![b"hello", b"world"];
+    /// let items = blocks.iter().map(|block| (MultiHash::generate_sha2_256(block), *block));
+    /// let mut failed = Vec::new();
+    /// let summary = MultiHash::verify_batch_each(items, 2, |index, result| {
+    ///     if result.is_err() {
+    ///         failed.push(index);
+    ///     }
+    /// });
+    /// assert_eq!(summary.passed, 2);
+    /// assert!(failed.is_empty());
+    /// # }
+    /// ```
+    pub fn verify_batch_each<I, R, F>(items: I, threads: usize, each: F) -> BatchSummary
+        where I: IntoIterator<Item=(MultiHash, R)>, I::IntoIter: Send, R: io::Read + Send,
+              F: FnMut(usize, error::validation::Result<()>)
+    {
+        MultiHash::verify_batch_each_with_backend(items, threads, &DefaultBackend, each)
+    }
+
+    /// As for `verify_batch_each`, except the hash implementations are
+    /// provided by the given backend.
+    pub fn verify_batch_each_with_backend<I, R, F>(items: I, threads: usize, backend: &(dyn HashBackend + Sync), mut each: F) -> BatchSummary
+        where I: IntoIterator<Item=(MultiHash, R)>, I::IntoIter: Send, R: io::Read + Send,
+              F: FnMut(usize, error::validation::Result<()>)
+    {
+        let threads = cmp::max(threads, 1);
+        let items = Mutex::new(items.into_iter().enumerate());
+        let (sender, receiver) = mpsc::sync_channel(threads);
+        let mut summary = BatchSummary::default();
+
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                let items = &items;
+                let sender = sender.clone();
+                let _ = scope.spawn(move || loop {
+                    // Release the lock before verifying so other threads can
+                    // take the next item
+                    let next = items.lock().unwrap().next();
+                    match next {
+                        Some((index, (multihash, reader))) => {
+                            let result = multihash.verify_reader_with_backend(reader, backend);
+                            // The receiver is only dropped if `each` panicked,
+                            // stop reading any more items
+                            if sender.send((index, result)).is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                });
+            }
+            // Only the workers' senders remain, so the receiver finishes once
+            // they all have
+            drop(sender);
+
+            for (index, result) in receiver {
+                summary.add(&result);
+                each(index, result);
+            }
+        });
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use MultiHash;
+
+    #[test]
+    fn empty() {
+        let items: Vec<(MultiHash, &[u8])> = vec![];
+        let verification = MultiHash::verify_batch(items);
+        assert!(verification.results.is_empty());
+        assert_eq!(verification.summary.to_string(), "0 passed, 0 failed, 0 unsupported");
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn in_order() {
+        use error::validation::ErrorKind;
+        use test_vectors::SHA2_256_ABC;
+        use MultiHashVariant;
+
+        let abc = MultiHash::new(MultiHashVariant::Sha2_256, &SHA2_256_ABC).unwrap();
+        let identity = MultiHash::new(MultiHashVariant::Identity, b"abc").unwrap();
+        let items: Vec<(MultiHash, &[u8])> = (0..100)
+            .map(|i| match i % 3 {
+                0 => (abc.clone(), &b"abc"[..]),
+                1 => (abc.clone(), &b"abd"[..]),
+                _ => (identity.clone(), &b"abc"[..]),
+            })
+            .collect();
+
+        let verification = MultiHash::verify_batch_with_threads(items, 4);
+        assert_eq!(verification.summary.passed, 34);
+        assert_eq!(verification.summary.failed, 33);
+        assert_eq!(verification.summary.unsupported, 33);
+        for (i, result) in verification.results.iter().enumerate() {
+            match (i % 3, result) {
+                (0, &Ok(())) => (),
+                (1, &Err(ref err)) => match *err.kind() {
+                    ErrorKind::Mismatch(_) => (),
+                    ref kind => panic!("unexpected error {:?} for item {}", kind, i),
+                },
+                (2, &Err(ref err)) => match *err.kind() {
+                    ErrorKind::NoValidator(_) => (),
+                    ref kind => panic!("unexpected error {:?} for item {}", kind, i),
+                },
+                (_, result) => panic!("unexpected result {:?} for item {}", result, i),
+            }
+        }
+    }
+
+    #[test]
+    fn each_panics() {
+        use std::panic;
+        use std::sync::atomic::{ AtomicUsize, Ordering };
+        use MultiHashVariant;
+
+        let taken = AtomicUsize::new(0);
+        let multihash = MultiHash::new(MultiHashVariant::Identity, b"abc").unwrap();
+        let items = (0..10000).map(|_| {
+            let _ = taken.fetch_add(1, Ordering::SeqCst);
+            (multihash.clone(), &b"abc"[..])
+        });
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            MultiHash::verify_batch_each(items, 2, |_, _| panic!("stop"))
+        }));
+        assert!(result.is_err());
+        assert!(taken.load(Ordering::SeqCst) < 100, "{} items taken", taken.load(Ordering::SeqCst));
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn each() {
+        use test_vectors::SHA2_256_ABC;
+        use MultiHashVariant;
+
+        let abc = MultiHash::new(MultiHashVariant::Sha2_256, &SHA2_256_ABC).unwrap();
+        let items = (0..1000).map(|i| (abc.clone(), if i % 10 == 0 { &b"abd"[..] } else { &b"abc"[..] }));
+
+        let mut seen = vec![false; 1000];
+        let summary = MultiHash::verify_batch_each(items, 3, |index, result| {
+            assert!(!seen[index], "item {} seen twice", index);
+            seen[index] = true;
+            assert_eq!(result.is_ok(), index % 10 != 0, "item {}", index);
+        });
+        assert_eq!(summary.passed, 900);
+        assert_eq!(summary.failed, 100);
+        assert!(seen.iter().all(|&seen| seen));
+    }
+}
@@ -1,54 +1,359 @@
-#[cfg(feature = "sha2")]
-mod sha2;
-
-use std::borrow::Cow;
+use std::io;
+use std::path::Path;
 use std::result;
 
+use constant_time_eq::constant_time_eq;
+
+use backend::{ DefaultBackend, HashBackend, HashState };
+use error;
+use file;
 use MultiHash;
 
-pub type Error = Cow<'static, str>; // TODO: Real error type
-pub type Result = result::Result<bool, Error>;
+mod batch;
+mod reader;
+
+pub use self::batch::{ BatchSummary, BatchVerification };
+pub use self::reader::VerifyingReader;
+
+pub type Result = result::Result<bool, error::validation::Error>;
+
+/// Adapts a `HashState` to be written to, for feeding it from a reader.
+struct StateWriter<'a>(&'a mut dyn HashState);
+
+impl<'a> io::Write for StateWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
 
 impl MultiHash {
+    /// Check that the given data hashes to this multihash.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoValidator` if there is no validator for the variant
+    /// available, this depends on which features the crate was compiled with.
+    ///
+    /// Returns `EmptyDigest` if the digest is empty, as it would match any
+    /// data.
+    ///
+    /// Returns `DigestTooLong` if the digest is longer than the hash output
+    /// of the variant.
+    ///
+    /// Returns `Mismatch` if the data does not hash to this multihash, with
+    /// both this multihash and the multihash the data hashed to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "generation", feature = "sha2"))] {
+    /// use mhash::MultiHash;
+    /// use mhash::error::validation::ErrorKind;
+    /// let multihash = MultiHash::generate_sha2_256(b"hello world");
+    /// assert!(multihash.verify(b"hello world").is_ok());
+    /// match *multihash.verify(b"goodbye world").unwrap_err().kind() {
+    ///     ErrorKind::Mismatch(ref mismatch) => {
+    ///         assert_eq!(mismatch.expected, multihash);
+    ///         assert_eq!(mismatch.computed, MultiHash::generate_sha2_256(b"goodbye world"));
+    ///     }
+    ///     ref kind => panic!("unexpected error {}", kind),
+    /// }
+    /// # }
+    /// ```
+    pub fn verify(&self, data: &[u8]) -> error::validation::Result<()> {
+        self.verify_with_backend(data, &DefaultBackend)
+    }
+
+    /// As for `verify`, except the hash implementation is provided by the
+    /// given backend.
+    pub fn verify_with_backend(&self, data: &[u8], backend: &dyn HashBackend) -> error::validation::Result<()> {
+        let mut state = self.validation_state(backend)?;
+        state.update(data);
+        self.check_hash(&state.finalize())
+    }
+
+    /// As for `verify`, except the data is read from the given reader until
+    /// EOF.
+    ///
+    /// # Errors
+    ///
+    /// As for `verify`, and additionally returns `Io` if reading fails.
+    pub fn verify_reader<R: io::Read>(&self, reader: R) -> error::validation::Result<()> {
+        self.verify_reader_with_backend(reader, &DefaultBackend)
+    }
+
+    /// As for `verify_reader`, except the hash implementation is provided by
+    /// the given backend.
+    pub fn verify_reader_with_backend<R: io::Read>(&self, mut reader: R, backend: &dyn HashBackend) -> error::validation::Result<()> {
+        let mut state = self.validation_state(backend)?;
+        let _ = io::copy(&mut reader, &mut StateWriter(&mut *state))?;
+        self.check_hash(&state.finalize())
+    }
+
+    /// As for `verify`, except the data is the contents of the file at the
+    /// given path.
+    ///
+    /// With the `memmap2` feature large regular files are memory-mapped,
+    /// other files are read in chunks. If a memory-mapped file is truncated
+    /// while being hashed the process may be killed by `SIGBUS`.
+    ///
+    /// # Errors
+    ///
+    /// As for `verify`, and additionally returns `Io` if the file cannot be
+    /// read.
+    pub fn verify_file<P: AsRef<Path>>(&self, path: P) -> error::validation::Result<()> {
+        self.verify_file_with_backend(path, &DefaultBackend)
+    }
+
+    /// As for `verify_file`, except the hash implementation is provided by
+    /// the given backend.
+    pub fn verify_file_with_backend<P: AsRef<Path>>(&self, path: P, backend: &dyn HashBackend) -> error::validation::Result<()> {
+        let mut state = self.validation_state(backend)?;
+        file::read_file(path.as_ref(), &mut |data| state.update(data))?;
+        self.check_hash(&state.finalize())
+    }
+
+    fn validation_state(&self, backend: &dyn HashBackend) -> error::validation::Result<Box<dyn HashState>> {
+        let variant = self.variant();
+        let length = if variant.is_extendable() { self.len() } else { variant.max_len() };
+        let state = backend.new_state(variant, length)
+            .ok_or_else(|| error::validation::ErrorKind::NoValidator(variant))?;
+        if self.len() == 0 {
+            return Err(error::validation::ErrorKind::EmptyDigest(variant).into());
+        }
+        Ok(state)
+    }
+
+    fn check_hash(&self, hash: &[u8]) -> error::validation::Result<()> {
+        if self.len() > hash.len() {
+            return Err(error::validation::ErrorKind::DigestTooLong(
+                    self.variant(), self.len(), hash.len()).into());
+        }
+        let computed = &hash[..self.len()];
+        if constant_time_eq(self.digest(), computed) {
+            Ok(())
+        } else {
+            let mismatch = error::validation::Mismatch {
+                expected: self.clone(),
+                computed: MultiHash::new_unchecked(self.variant(), computed),
+            };
+            Err(error::validation::ErrorKind::Mismatch(Box::new(mismatch)).into())
+        }
+    }
+
     /// Returns None if there is no validator for this digest type, otherwise
     /// the result of the validator
+    ///
+    /// There is a validator for every variant that can be generated, the
+    /// digest is compared against the hash output truncated to the digest
+    /// length, or for extendable-output variants a hash output of the digest
+    /// length.
     pub fn validate(&self, data: &[u8]) -> Option<Result> {
-        use MultiHashVariant::*;
-        match self.variant() {
-            Sha2_256 => validate_sha256(self, data),
-            Sha2_512 => validate_sha512(self, data),
-            _ => None
-        }
+        self.validate_with_backend(data, &DefaultBackend)
+    }
+
+    /// As for `validate`, except the hash implementation is provided by the
+    /// given backend.
+    pub fn validate_with_backend(&self, data: &[u8], backend: &dyn HashBackend) -> Option<Result> {
+        to_validate_result(self.verify_with_backend(data, backend))
+    }
+
+    /// As for `validate`, except the data is the contents of the file at the
+    /// given path, see `verify_file`.
+    pub fn validate_file<P: AsRef<Path>>(&self, path: P) -> Option<Result> {
+        self.validate_file_with_backend(path, &DefaultBackend)
+    }
+
+    /// As for `validate_file`, except the hash implementation is provided by
+    /// the given backend.
+    pub fn validate_file_with_backend<P: AsRef<Path>>(&self, path: P, backend: &dyn HashBackend) -> Option<Result> {
+        to_validate_result(self.verify_file_with_backend(path, backend))
     }
 }
 
-macro_rules! optional_validator {
-    ($feature:expr, $module:ident, $method:ident) => {
-        #[cfg(not(feature = $feature))]
-        pub fn $method(_multihash: &MultiHash, _data: &[u8]) -> Option<Result> {
-            None
+/// Converts the result of `verify` into the result of `validate`.
+fn to_validate_result(result: error::validation::Result<()>) -> Option<Result> {
+    match result {
+        Ok(()) => Some(Ok(true)),
+        Err(err) => match *err.kind() {
+            error::validation::ErrorKind::NoValidator(_) => None,
+            error::validation::ErrorKind::Mismatch(_) => Some(Ok(false)),
+            _ => Some(Err(err)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use error::validation::ErrorKind;
+    use { MultiHash, MultiHashVariant };
+
+    #[test]
+    fn no_validator() {
+        let multihash = MultiHash::new(MultiHashVariant::Identity, b"abc").unwrap();
+        match *multihash.verify(b"abc").unwrap_err().kind() {
+            ErrorKind::NoValidator(MultiHashVariant::Identity) => (),
+            ref kind => panic!("unexpected error {:?}", kind),
         }
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn backend() {
+        use backend::{ HashBackend, HashState };
+        use test_vectors::SHA2_256_ABC;
+        use VerifyingReader;
 
-        #[cfg(feature = $feature)]
-        pub fn $method(multihash: &MultiHash, data: &[u8]) -> Option<Result> {
-            Some($module::$method(multihash, data))
+        struct NoBackend;
+
+        impl HashBackend for NoBackend {
+            fn new_state(&self, _variant: MultiHashVariant, _length: usize) -> Option<Box<dyn HashState>> {
+                None
+            }
         }
-    };
-}
 
-macro_rules! optional_validators {
-    ($feature:expr, $module:ident, $($method:ident),+) => {
-        $(optional_validator!($feature, $module, $method);)*
-    };
-}
+        let multihash = MultiHash::new(MultiHashVariant::Sha2_256, &SHA2_256_ABC).unwrap();
+        assert!(multihash.verify_reader(&b"abc"[..]).is_ok());
+        match *multihash.verify_reader_with_backend(&b"abc"[..], &NoBackend).unwrap_err().kind() {
+            ErrorKind::NoValidator(MultiHashVariant::Sha2_256) => (),
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+        assert!(multihash.validate_file_with_backend("missing", &NoBackend).is_none());
+        assert!(VerifyingReader::with_backend(&b"abc"[..], multihash.clone(), &NoBackend).is_err());
+        let items = vec![(multihash.clone(), &b"abc"[..]), (multihash.clone(), &b"abc"[..])];
+        let summary = MultiHash::verify_batch_each_with_backend(items, 2, &NoBackend, |_, _| ());
+        assert_eq!(summary.unsupported, 2);
+    }
 
-optional_validators!("sha2", sha2, validate_sha256, validate_sha512);
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn verify() {
+        use test_vectors::SHA2_256_ABC;
+        let multihash = MultiHash::new(MultiHashVariant::Sha2_256, &SHA2_256_ABC).unwrap();
+        assert!(multihash.verify(b"abc").is_ok());
+        match *multihash.verify(b"abd").unwrap_err().kind() {
+            ErrorKind::Mismatch(ref mismatch) => {
+                assert_eq!(mismatch.expected, multihash);
+                assert_eq!(mismatch.computed.variant(), MultiHashVariant::Sha2_256);
+                assert_eq!(&mismatch.computed.digest()[..4], &[0xa5, 0x2d, 0x15, 0x9f]);
+            }
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+    }
 
-#[allow(dead_code)] // Will be dead if no validators are active
-fn validate_base(multihash: &MultiHash, hash: &[u8]) -> Result {
-    if multihash.len() > hash.len() {
-        return Err("Digest too long".into());
+    #[test]
+    fn result_size() {
+        use std::mem;
+        use error::validation::Result;
+        // Keep results small for batch verification of many items
+        assert!(mem::size_of::<Result<()>>() <= 64, "{}", mem::size_of::<Result<()>>());
+    }
+
+    #[test]
+    fn mismatch_display() {
+        use error::validation::Mismatch;
+        let mismatch = Mismatch {
+            expected: MultiHash::new(MultiHashVariant::Sha1, &[0x0a; 20]).unwrap(),
+            computed: MultiHash::new(MultiHashVariant::Sha1, &[0xb0; 20]).unwrap(),
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            "sha1 digest mismatch: \
+             expected 0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a, \
+             computed b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0");
     }
-    Ok(multihash.digest() == &hash[..multihash.len()])
-}
 
+    #[cfg(all(feature = "sha2", feature = "sha3"))]
+    #[test]
+    fn empty_digest() {
+        for &variant in &[MultiHashVariant::Sha2_256, MultiHashVariant::Shake128] {
+            let multihash = MultiHash::new(variant, &[]).unwrap();
+            match *multihash.verify(b"anything").unwrap_err().kind() {
+                ErrorKind::EmptyDigest(v) if v == variant => (),
+                ref kind => panic!("unexpected error {:?}", kind),
+            }
+            assert!(multihash.validate(b"anything").unwrap().is_err());
+        }
+    }
+
+    #[cfg(feature = "sha3")]
+    #[test]
+    fn shake_lengths() {
+        let multihash = MultiHash::new(MultiHashVariant::Shake128, &[
+            0x7f, 0x9c, 0x2b, 0xa4, 0xe8, 0x8f, 0x82, 0x7d,
+            0x61, 0x60, 0x45, 0x50, 0x76, 0x05, 0x85, 0x3e,
+        ]).unwrap();
+        assert!(multihash.verify(b"").is_ok());
+        assert!(multihash.verify(b"a").is_err());
+    }
+
+    #[cfg(all(feature = "generation", feature = "sha2"))]
+    #[test]
+    fn truncated() {
+        let multihash = MultiHash::generate_with_length(MultiHashVariant::Sha2_256, b"abc", 20).unwrap();
+        assert!(multihash.verify(b"abc").is_ok());
+        assert!(multihash.verify(b"abd").is_err());
+    }
+
+    // Every variant except application specific ones, as their
+    // registrations are changed by concurrently running tests
+    #[cfg(feature = "generation")]
+    const VARIANTS: &'static [MultiHashVariant] = &[
+        MultiHashVariant::Identity,
+        MultiHashVariant::Md4,
+        MultiHashVariant::Md5,
+        MultiHashVariant::Sha1,
+        MultiHashVariant::Sha2_256,
+        MultiHashVariant::Sha2_512,
+        MultiHashVariant::Sha2_224,
+        MultiHashVariant::Sha2_384,
+        MultiHashVariant::Sha2_512_224,
+        MultiHashVariant::Sha2_512_256,
+        MultiHashVariant::DblSha2_256,
+        MultiHashVariant::Sha3_224,
+        MultiHashVariant::Sha3_256,
+        MultiHashVariant::Sha3_384,
+        MultiHashVariant::Sha3_512,
+        MultiHashVariant::Shake128,
+        MultiHashVariant::Shake256,
+        MultiHashVariant::Keccak224,
+        MultiHashVariant::Keccak256,
+        MultiHashVariant::Keccak384,
+        MultiHashVariant::Keccak512,
+        MultiHashVariant::Blake2B,
+        MultiHashVariant::Blake2S,
+        MultiHashVariant::Blake2BSized { length: 1 },
+        MultiHashVariant::Blake2BSized { length: 15 },
+        MultiHashVariant::Blake2BSized { length: 16 },
+        MultiHashVariant::Blake2BSized { length: 64 },
+        MultiHashVariant::Blake2SSized { length: 1 },
+        MultiHashVariant::Blake2SSized { length: 32 },
+        MultiHashVariant::Ripemd160,
+        MultiHashVariant::Blake3,
+        MultiHashVariant::Murmur3_32,
+        MultiHashVariant::Murmur3X64_64,
+        MultiHashVariant::Xxh32,
+        MultiHashVariant::Xxh64,
+        MultiHashVariant::Xxh3_64,
+        MultiHashVariant::Xxh3_128,
+    ];
+
+    #[cfg(feature = "generation")]
+    #[test]
+    fn matches_generation() {
+        for &variant in VARIANTS {
+            match MultiHash::generate(variant, b"abc") {
+                Ok(multihash) => assert!(multihash.verify(b"abc").is_ok(), "{}", variant.name()),
+                Err(_) => {
+                    let multihash = MultiHash::new(variant, &[0]).unwrap();
+                    assert!(multihash.validate(b"abc").is_none(), "{}", variant.name());
+                }
+            }
+        }
+    }
+}
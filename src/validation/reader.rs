@@ -0,0 +1,155 @@
+use std::fmt;
+use std::io;
+
+use backend::{ DefaultBackend, HashBackend, HashState };
+use error;
+use MultiHash;
+
+/// Wraps a reader, checking at EOF that all data read through it matches an
+/// expected `MultiHash`.
+///
+/// Instead of signalling EOF by returning `Ok(0)`, a mismatch is reported by
+/// returning an `io::Error` of kind `InvalidData`, with an
+/// `error::validation::Mismatch` as its inner error.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(feature = "generation", feature = "sha2"))] {
+/// use std::io::Read;
+/// use mhash::{ MultiHash, VerifyingReader };
+/// use mhash::error::validation::Mismatch;
+/// let expected = MultiHash::generate_sha2_256(b"hello world");
+///
+/// let mut reader = VerifyingReader::new(&b"hello world"[..], expected.clone()).unwrap();
+/// let mut buffer = Vec::new();
+/// assert!(reader.read_to_end(&mut buffer).is_ok());
+///
+/// let mut reader = VerifyingReader::new(&b"goodbye world"[..], expected.clone()).unwrap();
+/// let err = reader.read_to_end(&mut buffer).unwrap_err();
+/// let mismatch = err.get_ref().unwrap().downcast_ref::<Mismatch>().unwrap();
+/// assert_eq!(mismatch.expected, expected);
+/// # }
+/// ```
+pub struct VerifyingReader<R> {
+    inner: R,
+    expected: MultiHash,
+    state: Option<Box<dyn HashState>>,
+    outcome: Option<error::validation::Result<()>>,
+}
+
+impl<R: io::Read> VerifyingReader<R> {
+    /// Wrap the reader, checking the data read against the expected
+    /// multihash.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoValidator` if there is no validator for the variant of the
+    /// expected multihash available, or `EmptyDigest` if its digest is empty.
+    pub fn new(inner: R, expected: MultiHash) -> error::validation::Result<VerifyingReader<R>> {
+        VerifyingReader::with_backend(inner, expected, &DefaultBackend)
+    }
+
+    /// As for `new`, except the hash implementation is provided by the given
+    /// backend.
+    pub fn with_backend(inner: R, expected: MultiHash, backend: &dyn HashBackend) -> error::validation::Result<VerifyingReader<R>> {
+        let state = expected.validation_state(backend)?;
+        Ok(VerifyingReader {
+            inner: inner,
+            expected: expected,
+            state: Some(state),
+            outcome: None,
+        })
+    }
+
+    /// The multihash the data read is checked against.
+    pub fn expected(&self) -> &MultiHash {
+        &self.expected
+    }
+
+    /// A reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Unwrap the reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+fn to_io_error(err: &error::validation::Error) -> io::Error {
+    match *err.kind() {
+        error::validation::ErrorKind::Mismatch(ref mismatch) => {
+            io::Error::new(io::ErrorKind::InvalidData, (**mismatch).clone())
+        }
+        ref kind => io::Error::new(io::ErrorKind::InvalidData, kind.to_string()),
+    }
+}
+
+impl<R: io::Read> io::Read for VerifyingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(ref outcome) = self.outcome {
+            return outcome.as_ref().map(|_| 0).map_err(to_io_error);
+        }
+        let read = self.inner.read(buf)?;
+        if read > 0 {
+            if let Some(ref mut state) = self.state {
+                state.update(&buf[..read]);
+            }
+        } else if !buf.is_empty() {
+            if let Some(state) = self.state.take() {
+                let outcome = self.expected.check_hash(&state.finalize());
+                let result = outcome.as_ref().map(|_| 0).map_err(to_io_error);
+                self.outcome = Some(outcome);
+                return result;
+            }
+        }
+        Ok(read)
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for VerifyingReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VerifyingReader")
+            .field("inner", &self.inner)
+            .field("expected", &self.expected)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use { MultiHash, MultiHashVariant, VerifyingReader };
+
+    #[test]
+    fn no_validator() {
+        let expected = MultiHash::new(MultiHashVariant::Identity, b"abc").unwrap();
+        assert!(VerifyingReader::new(&b"abc"[..], expected).is_err());
+    }
+
+    #[cfg(feature = "sha1")]
+    #[test]
+    fn mismatch() {
+        use std::io::{ ErrorKind, Read };
+        use error::validation::Mismatch;
+
+        let expected = MultiHash::new(MultiHashVariant::Sha1, &[
+            0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e,
+            0x25, 0x71, 0x78, 0x50, 0xc2, 0x6c, 0x9c, 0xd0, 0xd8, 0x9d,
+        ]).unwrap();
+
+        let mut buffer = Vec::new();
+        let mut reader = VerifyingReader::new(&b"abc"[..], expected.clone()).unwrap();
+        assert_eq!(reader.read_to_end(&mut buffer).unwrap(), 3);
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+
+        let mut reader = VerifyingReader::new(&b"abd"[..], expected.clone()).unwrap();
+        let err = reader.read_to_end(&mut buffer).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let mismatch = err.get_ref().unwrap().downcast_ref::<Mismatch>().unwrap();
+        assert_eq!(mismatch.expected, expected);
+        assert_eq!(mismatch.computed.variant(), MultiHashVariant::Sha1);
+        assert!(reader.read(&mut [0; 4]).is_err());
+    }
+}
@@ -1,6 +1,37 @@
 use error;
+use registry;
 use self::MultiHashVariant::*;
 
+static BLAKE2B_NAMES: [&'static str; 64] = [
+    "blake2b-8", "blake2b-16", "blake2b-24", "blake2b-32",
+    "blake2b-40", "blake2b-48", "blake2b-56", "blake2b-64",
+    "blake2b-72", "blake2b-80", "blake2b-88", "blake2b-96",
+    "blake2b-104", "blake2b-112", "blake2b-120", "blake2b-128",
+    "blake2b-136", "blake2b-144", "blake2b-152", "blake2b-160",
+    "blake2b-168", "blake2b-176", "blake2b-184", "blake2b-192",
+    "blake2b-200", "blake2b-208", "blake2b-216", "blake2b-224",
+    "blake2b-232", "blake2b-240", "blake2b-248", "blake2b-256",
+    "blake2b-264", "blake2b-272", "blake2b-280", "blake2b-288",
+    "blake2b-296", "blake2b-304", "blake2b-312", "blake2b-320",
+    "blake2b-328", "blake2b-336", "blake2b-344", "blake2b-352",
+    "blake2b-360", "blake2b-368", "blake2b-376", "blake2b-384",
+    "blake2b-392", "blake2b-400", "blake2b-408", "blake2b-416",
+    "blake2b-424", "blake2b-432", "blake2b-440", "blake2b-448",
+    "blake2b-456", "blake2b-464", "blake2b-472", "blake2b-480",
+    "blake2b-488", "blake2b-496", "blake2b-504", "blake2b-512",
+];
+
+static BLAKE2S_NAMES: [&'static str; 32] = [
+    "blake2s-8", "blake2s-16", "blake2s-24", "blake2s-32",
+    "blake2s-40", "blake2s-48", "blake2s-56", "blake2s-64",
+    "blake2s-72", "blake2s-80", "blake2s-88", "blake2s-96",
+    "blake2s-104", "blake2s-112", "blake2s-120", "blake2s-128",
+    "blake2s-136", "blake2s-144", "blake2s-152", "blake2s-160",
+    "blake2s-168", "blake2s-176", "blake2s-184", "blake2s-192",
+    "blake2s-200", "blake2s-208", "blake2s-216", "blake2s-224",
+    "blake2s-232", "blake2s-240", "blake2s-248", "blake2s-256",
+];
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 #[allow(non_camel_case_types)]
 /// The possible multihash variants.
@@ -9,6 +40,14 @@ pub enum MultiHashVariant {
     /// May be a prefix rather than a full copy.
     Identity,
 
+    /// A 128-bit [MD4][] digest. Cryptographically broken.
+    /// [MD4]: https://en.wikipedia.org/wiki/MD4
+    Md4,
+
+    /// A 128-bit [MD5][] digest. Cryptographically broken.
+    /// [MD5]: https://en.wikipedia.org/wiki/MD5
+    Md5,
+
     /// A 160-bit [SHA-1][] digest.
     /// [SHA-1]: https://en.wikipedia.org/wiki/SHA-1
     Sha1,
@@ -21,6 +60,22 @@ pub enum MultiHashVariant {
     /// [SHA-2]: https://en.wikipedia.org/wiki/SHA-2
     Sha2_512,
 
+    /// A 224-bit [SHA-2][] digest.
+    /// [SHA-2]: https://en.wikipedia.org/wiki/SHA-2
+    Sha2_224,
+
+    /// A 384-bit [SHA-2][] digest.
+    /// [SHA-2]: https://en.wikipedia.org/wiki/SHA-2
+    Sha2_384,
+
+    /// A 224-bit [SHA-2][] digest using the SHA-512/224 construction.
+    /// [SHA-2]: https://en.wikipedia.org/wiki/SHA-2
+    Sha2_512_224,
+
+    /// A 256-bit [SHA-2][] digest using the SHA-512/256 construction.
+    /// [SHA-2]: https://en.wikipedia.org/wiki/SHA-2
+    Sha2_512_256,
+
     /// A 512-bit [SHA-3][] digest.
     /// [SHA-3]: https://en.wikipedia.org/wiki/SHA-3
     Sha3_512,
@@ -33,6 +88,11 @@ pub enum MultiHashVariant {
     /// [SHA-3]: https://en.wikipedia.org/wiki/SHA-3
     Sha3_256,
 
+    /// A 256-bit [SHA-2][] digest of a 256-bit SHA-2 digest, as used in
+    /// Bitcoin.
+    /// [SHA-2]: https://en.wikipedia.org/wiki/SHA-2
+    DblSha2_256,
+
     /// A 224-bit [SHA-3][] digest.
     /// [SHA-3]: https://en.wikipedia.org/wiki/SHA-3
     Sha3_224,
@@ -53,6 +113,73 @@ pub enum MultiHashVariant {
     /// [BLAKE2s]: https://en.wikipedia.org/wiki/BLAKE_(hash_function)#BLAKE2
     Blake2S,
 
+    /// A [BLAKE2b][] digest with a specific output size (blake2b-8 through
+    /// blake2b-512).
+    /// [BLAKE2b]: https://en.wikipedia.org/wiki/BLAKE_(hash_function)#BLAKE2
+    Blake2BSized {
+        /// The output size of this variant in bytes, must be in the range
+        /// [1, 64].
+        length: usize
+    },
+
+    /// A [BLAKE2s][] digest with a specific output size (blake2s-8 through
+    /// blake2s-256).
+    /// [BLAKE2s]: https://en.wikipedia.org/wiki/BLAKE_(hash_function)#BLAKE2
+    Blake2SSized {
+        /// The output size of this variant in bytes, must be in the range
+        /// [1, 32].
+        length: usize
+    },
+
+    /// A 224-bit [Keccak][] digest, as used before SHA-3 was standardised.
+    /// [Keccak]: https://keccak.team/keccak.html
+    Keccak224,
+
+    /// A 256-bit [Keccak][] digest, as used before SHA-3 was standardised.
+    /// [Keccak]: https://keccak.team/keccak.html
+    Keccak256,
+
+    /// A 384-bit [Keccak][] digest, as used before SHA-3 was standardised.
+    /// [Keccak]: https://keccak.team/keccak.html
+    Keccak384,
+
+    /// A 512-bit [Keccak][] digest, as used before SHA-3 was standardised.
+    /// [Keccak]: https://keccak.team/keccak.html
+    Keccak512,
+
+    /// A 160-bit [RIPEMD-160][] digest.
+    /// [RIPEMD-160]: https://en.wikipedia.org/wiki/RIPEMD
+    Ripemd160,
+
+    /// A variable size [BLAKE3][] digest, 256-bit by default.
+    /// [BLAKE3]: https://github.com/BLAKE3-team/BLAKE3
+    Blake3,
+
+    /// A 32-bit [MurmurHash3][] digest. Not a cryptographic hash.
+    /// [MurmurHash3]: https://en.wikipedia.org/wiki/MurmurHash
+    Murmur3_32,
+
+    /// The first 64 bits of a x64 128-bit [MurmurHash3][] digest. Not a
+    /// cryptographic hash.
+    /// [MurmurHash3]: https://en.wikipedia.org/wiki/MurmurHash
+    Murmur3X64_64,
+
+    /// A 32-bit [xxHash][] digest. Not a cryptographic hash.
+    /// [xxHash]: https://xxhash.com
+    Xxh32,
+
+    /// A 64-bit [xxHash][] digest. Not a cryptographic hash.
+    /// [xxHash]: https://xxhash.com
+    Xxh64,
+
+    /// A 64-bit [XXH3][] digest. Not a cryptographic hash.
+    /// [XXH3]: https://xxhash.com
+    Xxh3_64,
+
+    /// A 128-bit [XXH3][] digest. Not a cryptographic hash.
+    /// [XXH3]: https://xxhash.com
+    Xxh3_128,
+
     /// An application specific MultiHash variant.
     ApplicationSpecific {
         /// The application specific code for this variant, must be in the
@@ -72,6 +199,8 @@ impl MultiHashVariant {
     pub fn from_code(code: usize) -> error::creation::Result<MultiHashVariant> {
         Ok(match code {
             0x00 => Identity,
+            0xd4 => Md4,
+            0xd5 => Md5,
             0x11 => Sha1,
             0x12 => Sha2_256,
             0x13 => Sha2_512,
@@ -81,9 +210,31 @@ impl MultiHashVariant {
             0x17 => Sha3_224,
             0x18 => Shake128,
             0x19 => Shake256,
+            0x1a => Keccak224,
+            0x1b => Keccak256,
+            0x1c => Keccak384,
+            0x1d => Keccak512,
+            0x1e => Blake3,
+            0x20 => Sha2_384,
+            0x22 => Murmur3X64_64,
+            0x23 => Murmur3_32,
 
             0x40 => Blake2B,
             0x41 => Blake2S,
+            0x56 => DblSha2_256,
+
+            0x1013 => Sha2_224,
+            0x1014 => Sha2_512_224,
+            0x1015 => Sha2_512_256,
+            0x1053 => Ripemd160,
+
+            0xb3e1 => Xxh32,
+            0xb3e2 => Xxh64,
+            0xb3e3 => Xxh3_64,
+            0xb3e4 => Xxh3_128,
+
+            0xb201 ... 0xb240 => Blake2BSized { length: code - 0xb200 },
+            0xb241 ... 0xb260 => Blake2SSized { length: code - 0xb240 },
 
             0x0400 ... 0x040f => ApplicationSpecific { code: code },
             _ => {
@@ -101,8 +252,27 @@ impl MultiHashVariant {
         Ok(variant)
     }
 
-    /// Validates that the length is consistent with this multihash variant.
+    /// Validates that the fields of this multihash variant are within their
+    /// allowed ranges, i.e. `Blake2BSized` lengths are 1-64, `Blake2SSized`
+    /// lengths are 1-32 and `ApplicationSpecific` codes are 0x0400-0x040f.
+    pub fn check_valid(self) -> error::creation::Result<()> {
+        let valid = match self {
+            Blake2BSized { length } => length >= 1 && length <= 64,
+            Blake2SSized { length } => length >= 1 && length <= 32,
+            ApplicationSpecific { code } => code >= 0x0400 && code <= 0x040f,
+            _ => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(error::creation::ErrorKind::InvalidVariant(self).into())
+        }
+    }
+
+    /// Validates that this multihash variant is valid and the length is
+    /// consistent with it.
     pub fn check_length(self, length: usize) -> error::creation::Result<()> {
+        self.check_valid()?;
         if length > self.max_len() {
             Err(error::creation::ErrorKind::LengthTooLong(self, length).into())
         } else {
@@ -113,13 +283,19 @@ impl MultiHashVariant {
     /// The maximum digest length allowed for this multihash variant.
     pub fn max_len(self) -> usize {
         match self {
-            Sha1 => 20,
-            Sha3_224 => 28,
-            Sha2_256 | Sha3_256 | Blake2S => 32,
-            Sha3_384 => 48,
-            Sha2_512 | Sha3_512 | Blake2B => 64,
-            Identity | Shake128 | Shake256 | ApplicationSpecific { .. }
-                => usize::max_value(),
+            Murmur3_32 | Xxh32 => 4,
+            Murmur3X64_64 | Xxh64 | Xxh3_64 => 8,
+            Md4 | Md5 | Xxh3_128 => 16,
+            Sha1 | Ripemd160 => 20,
+            Sha2_224 | Sha2_512_224 | Sha3_224 | Keccak224 => 28,
+            Sha2_256 | Sha2_512_256 | DblSha2_256 | Sha3_256 | Keccak256 | Blake2S => 32,
+            Sha2_384 | Sha3_384 | Keccak384 => 48,
+            Sha2_512 | Sha3_512 | Keccak512 | Blake2B => 64,
+            Blake2BSized { length } | Blake2SSized { length } => length,
+            Identity | Shake128 | Shake256 | Blake3 => usize::max_value(),
+            ApplicationSpecific { code } => {
+                registry::max_len(code).unwrap_or(usize::max_value())
+            }
             __Nonexhaustive => unreachable!(),
         }
     }
@@ -128,19 +304,46 @@ impl MultiHashVariant {
     pub fn code(self) -> usize {
         match self {
             Identity => 0x00,
+            Md4 => 0xd4,
+            Md5 => 0xd5,
             Sha1 => 0x11,
             Sha2_256 => 0x12,
             Sha2_512 => 0x13,
+            Sha2_224 => 0x1013,
+            Sha2_384 => 0x20,
+            Sha2_512_224 => 0x1014,
+            Sha2_512_256 => 0x1015,
+            DblSha2_256 => 0x56,
             Sha3_512 => 0x14,
             Sha3_384 => 0x15,
             Sha3_256 => 0x16,
             Sha3_224 => 0x17,
             Shake128 => 0x18,
             Shake256 => 0x19,
+            Keccak224 => 0x1a,
+            Keccak256 => 0x1b,
+            Keccak384 => 0x1c,
+            Keccak512 => 0x1d,
+            Ripemd160 => 0x1053,
+            Blake3 => 0x1e,
+            Murmur3X64_64 => 0x22,
+            Murmur3_32 => 0x23,
+            Xxh32 => 0xb3e1,
+            Xxh64 => 0xb3e2,
+            Xxh3_64 => 0xb3e3,
+            Xxh3_128 => 0xb3e4,
             Blake2B => 0x40,
             Blake2S => 0x41,
+            Blake2BSized { length } => {
+                assert!(length >= 1 && length <= 64, "blake2b length {} outside allowed range 1-64", length);
+                0xb200 + length
+            }
+            Blake2SSized { length } => {
+                assert!(length >= 1 && length <= 32, "blake2s length {} outside allowed range 1-32", length);
+                0xb240 + length
+            }
             ApplicationSpecific { code } => {
-                assert!(code > 0x0400 && code < 0x040f, "application specific code {:#04x} outside allowed range 0x0400-0x040f", code);
+                assert!(code >= 0x0400 && code <= 0x040f, "application specific code {:#04x} outside allowed range 0x0400-0x040f", code);
                 code
             }
             __Nonexhaustive => unreachable!(),
@@ -151,24 +354,177 @@ impl MultiHashVariant {
     pub fn name(self) -> &'static str {
         match self {
             Identity => "identity",
+            Md4 => "md4",
+            Md5 => "md5",
             Sha1 => "sha1",
             Sha2_256 => "sha2-256",
             Sha2_512 => "sha2-512",
+            Sha2_224 => "sha2-224",
+            Sha2_384 => "sha2-384",
+            Sha2_512_224 => "sha2-512-224",
+            Sha2_512_256 => "sha2-512-256",
+            DblSha2_256 => "dbl-sha2-256",
             Sha3_224 => "sha3-224",
             Sha3_256 => "sha3-256",
             Sha3_384 => "sha3-384",
             Sha3_512 => "sha3-512",
             Shake128 => "shake-128",
             Shake256 => "shake-256",
+            Keccak224 => "keccak-224",
+            Keccak256 => "keccak-256",
+            Keccak384 => "keccak-384",
+            Keccak512 => "keccak-512",
+            Ripemd160 => "ripemd-160",
+            Blake3 => "blake3",
+            Murmur3X64_64 => "murmur3-x64-64",
+            Murmur3_32 => "murmur3-32",
+            Xxh32 => "xxh-32",
+            Xxh64 => "xxh-64",
+            Xxh3_64 => "xxh3-64",
+            Xxh3_128 => "xxh3-128",
             Blake2B => "blake2b",
             Blake2S => "blake2s",
+            Blake2BSized { length } => {
+                assert!(length >= 1 && length <= 64, "blake2b length {} outside allowed range 1-64", length);
+                BLAKE2B_NAMES[length - 1]
+            }
+            Blake2SSized { length } => {
+                assert!(length >= 1 && length <= 32, "blake2s length {} outside allowed range 1-32", length);
+                BLAKE2S_NAMES[length - 1]
+            }
             ApplicationSpecific { code } => {
-                assert!(code > 0x0400 && code < 0x040f, "application specific code {:#04x} outside allowed range 0x0400-0x040f", code);
-                "app-specific"
+                assert!(code >= 0x0400 && code <= 0x040f, "application specific code {:#04x} outside allowed range 0x0400-0x040f", code);
+                registry::name(code).unwrap_or("app-specific")
             }
             __Nonexhaustive => unreachable!(),
         }
     }
 
+    /// Whether this variant is a cryptographic hash. This is false for the
+    /// identity "hash", fast non-cryptographic hashes and application specific
+    /// variants (as nothing is known about them), all of which should be
+    /// rejected where collision resistance is required.
+    pub fn is_cryptographic(self) -> bool {
+        match self {
+            Md4 | Md5 | Sha1
+                | Sha2_256 | Sha2_512 | Sha2_224 | Sha2_384
+                | Sha2_512_224 | Sha2_512_256 | DblSha2_256
+                | Sha3_224 | Sha3_256 | Sha3_384 | Sha3_512
+                | Shake128 | Shake256
+                | Keccak224 | Keccak256 | Keccak384 | Keccak512
+                | Blake2B | Blake2S | Blake2BSized { .. } | Blake2SSized { .. }
+                | Blake3 | Ripemd160 => true,
+            Identity
+                | Murmur3_32 | Murmur3X64_64
+                | Xxh32 | Xxh64 | Xxh3_64 | Xxh3_128
+                | ApplicationSpecific { .. } => false,
+            __Nonexhaustive => unreachable!(),
+        }
+    }
+
+    /// Whether this variant is a cryptographic hash with practical collision
+    /// attacks known against it, so should not be relied on where an attacker
+    /// may choose the data being hashed.
+    pub fn is_broken(self) -> bool {
+        match self {
+            Md4 | Md5 | Sha1 => true,
+            Identity
+                | Sha2_256 | Sha2_512 | Sha2_224 | Sha2_384
+                | Sha2_512_224 | Sha2_512_256 | DblSha2_256
+                | Sha3_224 | Sha3_256 | Sha3_384 | Sha3_512
+                | Shake128 | Shake256
+                | Keccak224 | Keccak256 | Keccak384 | Keccak512
+                | Blake2B | Blake2S | Blake2BSized { .. } | Blake2SSized { .. }
+                | Blake3 | Ripemd160
+                | Murmur3_32 | Murmur3X64_64
+                | Xxh32 | Xxh64 | Xxh3_64 | Xxh3_128
+                | ApplicationSpecific { .. } => false,
+            __Nonexhaustive => unreachable!(),
+        }
+    }
+
+    /// Whether this variant is an extendable-output function, i.e. can
+    /// produce digests of any length.
+    pub fn is_extendable(self) -> bool {
+        match self {
+            Shake128 | Shake256 | Blake3 => true,
+            Identity | Md4 | Md5 | Sha1
+                | Sha2_256 | Sha2_512 | Sha2_224 | Sha2_384
+                | Sha2_512_224 | Sha2_512_256 | DblSha2_256
+                | Sha3_224 | Sha3_256 | Sha3_384 | Sha3_512
+                | Keccak224 | Keccak256 | Keccak384 | Keccak512
+                | Blake2B | Blake2S | Blake2BSized { .. } | Blake2SSized { .. }
+                | Ripemd160
+                | Murmur3_32 | Murmur3X64_64
+                | Xxh32 | Xxh64 | Xxh3_64 | Xxh3_128
+                | ApplicationSpecific { .. } => false,
+            __Nonexhaustive => unreachable!(),
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use MultiHashVariant;
+
+    #[test]
+    fn cryptographic() {
+        assert!(MultiHashVariant::Sha2_256.is_cryptographic());
+        assert!(MultiHashVariant::Md5.is_cryptographic());
+        assert!(!MultiHashVariant::from_code(0x23).unwrap().is_cryptographic());
+        assert!(!MultiHashVariant::from_code(0xb3e3).unwrap().is_cryptographic());
+        assert!(!MultiHashVariant::Identity.is_cryptographic());
+    }
+
+    #[test]
+    fn broken() {
+        assert!(MultiHashVariant::from_code(0xd5).unwrap().is_broken());
+        assert!(MultiHashVariant::Md4.is_broken());
+        assert!(!MultiHashVariant::Sha2_256.is_broken());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(MultiHashVariant::Blake2BSized { length: 100 }.check_length(10).is_err());
+        assert!(MultiHashVariant::Blake2SSized { length: 0 }.check_length(0).is_err());
+        assert!(MultiHashVariant::ApplicationSpecific { code: 0x12 }.check_valid().is_err());
+        assert!(MultiHashVariant::Blake2SSized { length: 32 }.check_length(32).is_ok());
+    }
+
+    #[test]
+    fn extendable() {
+        assert!(MultiHashVariant::Shake128.is_extendable());
+        assert!(MultiHashVariant::from_code(0x1e).unwrap().is_extendable());
+        assert!(!MultiHashVariant::Sha3_256.is_extendable());
+    }
+
+    #[test]
+    fn blake2b_sized() {
+        let variant = MultiHashVariant::from_code(0xb220).unwrap();
+        assert_eq!(variant, MultiHashVariant::Blake2BSized { length: 32 });
+        assert_eq!(variant.code(), 0xb220);
+        assert_eq!(variant.max_len(), 32);
+        assert_eq!(variant.name(), "blake2b-256");
+    }
+
+    #[test]
+    fn blake2s_sized() {
+        let variant = MultiHashVariant::from_code(0xb241).unwrap();
+        assert_eq!(variant, MultiHashVariant::Blake2SSized { length: 1 });
+        assert_eq!(variant.code(), 0xb241);
+        assert_eq!(variant.max_len(), 1);
+        assert_eq!(variant.name(), "blake2s-8");
+    }
+
+    #[test]
+    fn blake2_sized_bounds() {
+        assert!(MultiHashVariant::from_code(0xb200).is_err());
+        assert_eq!(
+            MultiHashVariant::from_code(0xb240).unwrap().name(),
+            "blake2b-512");
+        assert_eq!(
+            MultiHashVariant::from_code(0xb260).unwrap().name(),
+            "blake2s-256");
+        assert!(MultiHashVariant::from_code(0xb261).is_err());
+    }
+}